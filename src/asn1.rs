@@ -1,25 +1,47 @@
 use anyhow::{bail, Error, Result};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset, Utc};
 use napi::{
-	bindgen_prelude::{Array, Buffer},
+	bindgen_prelude::{Array, Buffer, Either, Null},
 	Env, JsArrayBuffer, JsBigInt, JsUnknown,
 };
-use num_bigint::BigInt;
+use num_bigint::{BigInt, Sign};
 use rasn::{
-	ber::{decode, encode},
+	ber::decode,
 	types::{
-		Any, BitString, BmpString, Class, GeneralString, Ia5String, NumericString, OctetString,
-		PrintableString, UniversalString, Utf8String, VisibleString,
+		Any, BitString, Class, GeneralString, Ia5String, NumericString, ObjectIdentifier,
+		OctetString, PrintableString, UniversalString, Utf8String, VisibleString,
 	},
 	Decode, Tag,
 };
 
 use crate::{
+	certificate::{
+		get_attribute, get_authority_info_access, get_authority_key_id_matches,
+		get_certificate_chain, get_certificate_policies, get_crl_distribution_points,
+		get_extensions, get_general_names, ASN1AccessDescription, ASN1Attribute, ASN1Certificate,
+		ASN1DistributionPoint, ASN1Extension, ASN1GeneralName, ASN1GeneralNamesOptions,
+		ASN1PolicyInformation,
+	},
+	constants::ASN1_DATE_TIME_NO_EXPIRY,
 	get_js_array_from_asn_iter, get_js_big_int_from_big_int, get_js_context_tag_from_asn1_context,
-	objects::{ASN1BitString, ASN1Context, ASN1ContextTag, ASN1Object, ASN1RawBitString, ASN1OID},
+	get_js_unknown_from_asn1_data,
+	objects::{
+		get_oid_elements, ASN1BitString, ASN1Context, ASN1ContextTag, ASN1Object, ASN1RawBitString,
+		ASN1OID,
+	},
+	options::{
+		get_decode_options, with_date_encoding_policy, with_decode_options, ASN1DateEncodingPolicy,
+		ASN1DecodeOptions,
+	},
+	schema::{get_schema_kind, is_kind_tag, ASN1Schema},
 	types::{ASN1Data, JsType},
-	utils::{get_utc_date_time_from_asn1_milli, get_vec_from_js_unknown},
-	ASN1NAPIError,
+	utils::{
+		content_length, get_date_time_from_asn1, get_digest, get_string_from_latin1,
+		get_string_from_utf16_be, get_string_kind_from_tag, get_time_nanos_from_asn1,
+		get_time_string_from_asn1, get_utc_date_time_from_asn1_milli, get_vec_from_js_unknown,
+		header_length, is_string_tag, split_elements, tag_length,
+	},
+	writer, ASN1NAPIError,
 };
 
 /// Convert ASN1 BER encoded data to JS native types. This is the main decoder
@@ -31,13 +53,14 @@ pub struct ASN1Decoder {
 	js_type: JsType,
 	data: Vec<u8>,
 	is_constructed: bool,
+	options: ASN1DecodeOptions,
 }
 
 /// Convert ASN1Data into ASN1 encoded data. This is the main encoder
 /// class for encoding to ASN1 encoded data.
 #[napi(js_name = "ASN1Encoder")]
 #[derive(Clone, Eq, PartialEq, Debug)]
-pub struct ASN1Encoder(ASN1Data);
+pub struct ASN1Encoder(ASN1Data, ASN1DateEncodingPolicy);
 
 /// ASN1 Iterator for sequences. Sequences use lazy loading iterators allowing
 /// for chaining of operations while only executing on a consumer ensuring
@@ -48,6 +71,34 @@ pub struct ASN1Iterator {
 	sequence: Vec<Any>,
 	length: usize,
 	index: usize,
+	options: ASN1DecodeOptions,
+}
+
+/// An element of the flattened view of ASN1 data, addressed by its path.
+#[napi(object, js_name = "ASN1FlatEntry")]
+pub struct ASN1FlatEntry {
+	/// Path of the element as accepted by `at`.
+	pub path: String,
+	pub r#type: String,
+	#[napi(ts_type = "ASN1AnyJS")]
+	pub value: JsUnknown,
+}
+
+/// ASN.1 encoded data and its digest.
+#[napi(object, js_name = "ASN1EncodedDigest")]
+pub struct ASN1EncodedDigest {
+	pub der: Buffer,
+	pub digest: Buffer,
+}
+
+/// The leading elements of a Sequence decoded to JS and the remaining
+/// elements as a decoder.
+#[napi(object, object_from_js = false, js_name = "ASN1HeadAndTail")]
+pub struct ASN1HeadAndTail {
+	#[napi(ts_type = "any[]")]
+	pub head: Array,
+	#[napi(ts_type = "ASN1Decoder | null")]
+	pub tail: Either<ASN1Decoder, Null>,
 }
 
 #[napi]
@@ -56,27 +107,39 @@ impl ASN1Iterator {
 	pub fn len(&self) -> usize {
 		self.length
 	}
+
+	/// Use the provided decode options for the decoded items.
+	pub(crate) fn with_options(mut self, options: ASN1DecodeOptions) -> Self {
+		self.options = options;
+		self
+	}
 }
 
 #[napi]
 impl ASN1Encoder {
-	/// Create a new ASN1Encoder instance from any ASN1 encodable type.
+	/// Create a new ASN1Encoder instance from any ASN1 encodable type, with
+	/// an optional policy for how dates are encoded.
 	#[napi(constructor)]
-	pub fn js_new(#[napi(ts_arg_type = "ASN1AnyJS")] data: JsUnknown) -> Result<Self> {
-		Ok(Self(ASN1Data::try_from(data)?))
+	pub fn js_new(
+		#[napi(ts_arg_type = "ASN1AnyJS")] data: JsUnknown,
+		policy: Option<ASN1DateEncodingPolicy>,
+	) -> Result<Self> {
+		let policy = policy.unwrap_or_default();
+		policy.validate()?;
+
+		let data = with_date_encoding_policy(&policy, || ASN1Data::try_from(data))?;
+
+		Ok(Self(data, policy))
 	}
 
 	/// Create a new ANS1toJS instance from ASN1Data.
 	pub fn new(data: ASN1Data) -> Self {
-		Self(data)
+		Self(data, ASN1DateEncodingPolicy::default())
 	}
 
 	/// Encode ASN1Data to a Vec<u8> of ASN.1 encoded data.
 	pub(crate) fn encode(&self) -> Result<Vec<u8>> {
-		match encode(&self.0) {
-			Ok(data) => Ok(data),
-			Err(_) => bail!(ASN1NAPIError::InvalidDataEncoding),
-		}
+		with_date_encoding_policy(&self.1, || writer::encode(&self.0))
 	}
 
 	/// Encode the ASN.1 data as an array buffer.
@@ -91,6 +154,19 @@ impl ASN1Encoder {
 	pub fn to_base64(&self) -> Result<String> {
 		Ok(base64::encode(self.encode()?))
 	}
+
+	/// Encode the ASN.1 data and compute the SHA-2 digest of the encoding,
+	/// such as for signing a TBSCertificate.
+	#[napi(ts_args_type = "algorithm: 'sha256' | 'sha384' | 'sha512'")]
+	pub fn encode_with_digest(&self, algorithm: String) -> Result<ASN1EncodedDigest> {
+		let der = self.encode()?;
+		let digest = get_digest(algorithm, &der)?;
+
+		Ok(ASN1EncodedDigest {
+			der: der.into(),
+			digest: digest.into(),
+		})
+	}
 }
 
 #[napi]
@@ -99,12 +175,33 @@ impl ASN1Decoder {
 	#[napi(constructor)]
 	pub fn js_new(
 		#[napi(ts_arg_type = "string | null | number[] | Buffer | ArrayBuffer")] data: JsUnknown,
+		options: Option<ASN1DecodeOptions>,
 	) -> Result<Self> {
-		Ok(Self::new(get_vec_from_js_unknown(data)?))
+		Ok(Self::new_with_options(
+			get_vec_from_js_unknown(data)?,
+			options.unwrap_or_default(),
+		))
 	}
 
-	/// Create a new ASN1Decoder instance from ASN1 encoded data.
+	/// Create a new ASN1Decoder instance from ASN1 encoded data, using the
+	/// decode options currently in effect.
 	pub fn new(data: Vec<u8>) -> Self {
+		Self::new_with_options(data, get_decode_options())
+	}
+
+	/// Create a new ASN1Decoder instance from ASN1 encoded data and options.
+	pub(crate) fn new_with_options(mut data: Vec<u8>, options: ASN1DecodeOptions) -> Self {
+		// Strip an erroneous constructed bit from primitive only INTEGER and
+		// OBJECT IDENTIFIER tags, and from OCTET STRING tags unless the
+		// contents are a valid segmented OCTET STRING
+		if options.lenient.unwrap_or(false) && !is_segmented_octet_string(&data) {
+			if let Some(first) = data.first_mut() {
+				if matches!(*first, 0x22 | 0x24 | 0x26) {
+					*first &= !0x20;
+				}
+			}
+		}
+
 		// Match constructed Sequence/Set tag
 		let bit = match *data.first().unwrap_or(&0x5) as u32 {
 			0x30 => 0x10, // Sequence
@@ -137,9 +234,20 @@ impl ASN1Decoder {
 			tag,
 			data,
 			is_constructed,
+			options,
 		}
 	}
 
+	/// Use the provided decode options for this instance.
+	pub(crate) fn with_options(self, options: ASN1DecodeOptions) -> Self {
+		Self::new_with_options(self.data, options)
+	}
+
+	/// Get the decode options for this instance.
+	pub(crate) fn get_options(&self) -> &ASN1DecodeOptions {
+		&self.options
+	}
+
 	/// Get the JsType of the encoded data.
 	pub fn get_js_type(&self) -> &JsType {
 		&self.js_type
@@ -185,12 +293,30 @@ impl ASN1Decoder {
 
 	/// Decode ASN1 encoded data.
 	pub(crate) fn decode<T: Decode>(&self) -> Result<T> {
-		match decode(&self.data) {
+		self.check_value_length()?;
+
+		match with_decode_options(&self.options, || decode(&self.data)) {
 			Ok(data) => Ok(data),
 			Err(_) => bail!(ASN1NAPIError::MalformedData),
 		}
 	}
 
+	/// Check the declared length of a string value against the maximum value
+	/// length, before the contents are read.
+	fn check_value_length(&self) -> Result<()> {
+		if let Some(max_value_length) = self.options.max_value_length {
+			if matches!(self.tag, Tag::OCTET_STRING | Tag::BIT_STRING) || is_string_tag(self.tag) {
+				match content_length(&self.data) {
+					Ok(length) if length as u64 <= u64::from(max_value_length) => {}
+					Ok(_) => bail!(ASN1NAPIError::InvalidValueLength),
+					Err(_) => bail!(ASN1NAPIError::MalformedData),
+				}
+			}
+		}
+
+		Ok(())
+	}
+
 	/// Get a ASN1BitString object.
 	pub(crate) fn get_raw_bit_string(&self) -> Result<ASN1RawBitString> {
 		self.decode::<ASN1RawBitString>()
@@ -201,6 +327,202 @@ impl ASN1Decoder {
 		self.decode::<ASN1Context>()
 	}
 
+	/// Get the elements contained in constructed data.
+	pub(crate) fn get_elements(&self) -> Result<Vec<Any>> {
+		if let Ok(elements) = split_elements(self.get_contents()?) {
+			Ok(elements
+				.into_iter()
+				.map(|element| Any::new(element.to_vec()))
+				.collect())
+		} else {
+			bail!(ASN1NAPIError::MalformedData)
+		}
+	}
+
+	/// Get the contents of the encoded data, without the tag and length.
+	pub(crate) fn get_contents(&self) -> Result<&[u8]> {
+		let data = self.get_raw();
+
+		if let (Ok(header), Ok(length)) = (header_length(data), content_length(data)) {
+			if let Some(contents) = header
+				.checked_add(length)
+				.and_then(|end| data.get(header..end))
+			{
+				return Ok(contents);
+			}
+		}
+
+		bail!(ASN1NAPIError::MalformedData)
+	}
+
+	/// Get the identifier octets of the encoded data, including every octet
+	/// of a high tag number.
+	pub(crate) fn get_tag_bytes(&self) -> Result<&[u8]> {
+		let data = self.get_raw();
+
+		if let Ok(length) = tag_length(data) {
+			match data[..length].last() {
+				Some(last) if length == 1 || last & 0x80 == 0 => return Ok(&data[..length]),
+				_ => {}
+			}
+		}
+
+		bail!(ASN1NAPIError::MalformedData)
+	}
+
+	/// Get the short name of the kind of element used in a shape
+	/// fingerprint, or the identifier octets in hex for other kinds.
+	fn get_shape_name(&self) -> Result<String> {
+		let identifier = self.get_tag_bytes()?;
+		let name = match (identifier, self.tag) {
+			([byte], _) if byte & 0xC0 == 0x80 => return Ok(format!("ctx{}", byte & 0x1F)),
+			([byte], tag) if byte & 0xC0 == 0x00 => match tag {
+				Tag::BOOL => "bool",
+				Tag::INTEGER => "int",
+				Tag::BIT_STRING => "bits",
+				Tag::OCTET_STRING => "bytes",
+				Tag::NULL => "null",
+				Tag::OBJECT_IDENTIFIER => "oid",
+				Tag::SEQUENCE => "seq",
+				Tag::SET => "set",
+				Tag::UTC_TIME | Tag::GENERALIZED_TIME => "date",
+				tag if is_string_tag(tag) => "str",
+				_ => return Ok(hex::encode(identifier)),
+			},
+			_ => return Ok(hex::encode(identifier)),
+		};
+
+		Ok(name.to_string())
+	}
+
+	/// Get decoders for the elements contained in constructed data.
+	pub(crate) fn get_element_decoders(&self) -> Result<Vec<ASN1Decoder>> {
+		Ok(self
+			.get_elements()?
+			.into_iter()
+			.map(|element| Self::new_with_options(element.into_bytes(), self.options.clone()))
+			.collect())
+	}
+
+	/// Get a decoder for the element at a path.
+	pub(crate) fn get_at<T: AsRef<str>>(&self, path: T) -> Result<ASN1Decoder> {
+		let mut current = self.clone();
+
+		for segment in path
+			.as_ref()
+			.split('/')
+			.filter(|segment| !segment.is_empty())
+		{
+			let next = if segment == "contains" {
+				if current.tag.class != Class::Context || !current.is_constructed {
+					bail!(ASN1NAPIError::InvalidPath);
+				}
+
+				current.get_element_decoders()?.into_iter().next()
+			} else if let Ok(index) = segment.parse::<usize>() {
+				if !matches!(current.tag, Tag::SEQUENCE | Tag::SET) {
+					bail!(ASN1NAPIError::InvalidPath);
+				}
+
+				current.get_element_decoders()?.into_iter().nth(index)
+			} else {
+				None
+			};
+
+			match next {
+				Some(next) => current = next,
+				None => bail!(ASN1NAPIError::InvalidPath),
+			}
+		}
+
+		Ok(current)
+	}
+
+	/// Decode as the type named in a schema.
+	fn get_schema_value(&self, env: Env, kind: &str) -> Result<JsUnknown> {
+		let kind = get_schema_kind(kind)?;
+
+		if !is_kind_tag(kind, self.tag) {
+			bail!(ASN1NAPIError::InvalidSchema);
+		}
+
+		Ok(match kind {
+			"integer" => self.into_big_int(env)?.into_unknown()?,
+			"boolean" => env.get_boolean(self.into_bool()?)?.into_unknown(),
+			"string" => env.create_string(&self.into_string()?)?.into_unknown(),
+			"oid" => get_js_unknown_from_asn1_data(
+				env,
+				ASN1Data::Object(ASN1Object::Oid(self.into_oid()?)),
+			)?,
+			"buffer" => env
+				.create_buffer_with_data(self.into_bytes()?)?
+				.into_raw()
+				.into_unknown(),
+			"date" => env
+				.create_date(self.into_date()?.timestamp_millis() as f64)?
+				.into_unknown(),
+			_ => get_js_unknown_from_asn1_data(env, ASN1Data::try_from(self.clone())?)?,
+		})
+	}
+
+	/// Append the flattened elements to the entries, descending into
+	/// sequences, sets and explicit context tags.
+	fn flatten_into(&self, env: Env, path: String, entries: &mut Vec<ASN1FlatEntry>) -> Result<()> {
+		let segment = |name: &dyn std::fmt::Display| {
+			if path.is_empty() {
+				name.to_string()
+			} else {
+				format!("{}/{}", path, name)
+			}
+		};
+
+		if matches!(self.tag, Tag::SEQUENCE | Tag::SET) {
+			for (index, element) in self.get_element_decoders()?.iter().enumerate() {
+				element.flatten_into(env, segment(&index), entries)?;
+			}
+		} else if self.tag.class == Class::Context && self.is_constructed {
+			self.get_at("contains")?
+				.flatten_into(env, segment(&"contains"), entries)?;
+		} else {
+			let data = ASN1Data::try_from(self.clone())?;
+
+			entries.push(ASN1FlatEntry {
+				path,
+				r#type: data.get_type_name().to_string(),
+				value: get_js_unknown_from_asn1_data(env, data)?,
+			});
+		}
+
+		Ok(())
+	}
+
+	/// Get the GeneralNames of the subject alternative name extension from a
+	/// certificate, or the GeneralNames themselves.
+	fn get_general_names(&self) -> Result<Vec<ASN1Decoder>> {
+		let elements = self.get_element_decoders()?;
+
+		if elements
+			.iter()
+			.all(|element| element.get_tag().class == Class::Context)
+		{
+			return Ok(elements);
+		}
+
+		for extension in get_extensions(self)? {
+			if get_oid_elements(&extension.oid)? == get_oid_elements("subjectAltName")? {
+				return Self::new_with_options(extension.value.to_vec(), self.options.clone())
+					.get_element_decoders();
+			}
+		}
+
+		Ok(vec![])
+	}
+
+	/// Iterate over the elements of a SET.
+	pub(crate) fn into_set_iter(self) -> Result<ASN1Iterator> {
+		Ok(ASN1Iterator::from(self.get_elements()?).with_options(self.options))
+	}
+
 	/// Decode into Any.
 	pub(crate) fn into_any(self) -> Result<Any> {
 		self.decode::<Any>()
@@ -228,26 +550,94 @@ impl ASN1Decoder {
 		get_js_big_int_from_big_int(env, self.decode::<BigInt>()?)
 	}
 
+	/// Decode a SEQUENCE OF INTEGER into big integers of any width.
+	pub(crate) fn get_big_ints(&self) -> Result<Vec<BigInt>> {
+		if self.tag != Tag::SEQUENCE {
+			bail!(ASN1NAPIError::MalformedData);
+		}
+
+		self.get_element_decoders()?
+			.iter()
+			.map(|element| element.decode::<BigInt>())
+			.collect()
+	}
+
+	/// Convert a SEQUENCE OF INTEGER to an array of JS big integers.
+	#[napi(ts_return_type = "bigint[]")]
+	pub fn into_big_int_array(&self, env: Env) -> Result<Array> {
+		let values = self.get_big_ints()?;
+		let mut array = env.create_array(values.len() as u32)?;
+
+		for (index, value) in values.into_iter().enumerate() {
+			array.set(index as u32, get_js_big_int_from_big_int(env, value)?)?;
+		}
+
+		Ok(array)
+	}
+
+	/// Convert a non-negative integer to its minimal unsigned bytes in
+	/// little-endian order.
+	pub(crate) fn get_unsigned_bytes_le(&self) -> Result<Vec<u8>> {
+		match self.decode::<BigInt>()?.to_bytes_le() {
+			(Sign::Minus, _) => bail!(ASN1NAPIError::InvalidNegativeInteger),
+			(_, bytes) => Ok(bytes),
+		}
+	}
+
+	/// Convert a non-negative integer to a little-endian buffer.
+	#[napi(js_name = "intoIntegerBytesLE")]
+	pub fn into_integer_bytes_le(&self) -> Result<Buffer> {
+		Ok(self.get_unsigned_bytes_le()?.into())
+	}
+
 	/// Convert to a boolean.
 	#[napi]
 	pub fn into_bool(&self) -> Result<bool> {
 		self.decode::<bool>()
 	}
 
+	/// Convert to null, checking that the data is a NULL.
+	#[napi]
+	pub fn into_null(&self) -> Result<Null> {
+		self.decode::<()>()?;
+
+		Ok(Null)
+	}
+
 	/// Convert to a string.
 	#[napi]
 	pub fn into_string(&self) -> Result<String> {
-		Ok(match *self.get_tag() {
+		self.check_value_length()?;
+
+		let value: String = match *self.get_tag() {
 			Tag::PRINTABLE_STRING => self.decode::<PrintableString>()?.as_str().into(),
-			Tag::BMP_STRING => self.decode::<BmpString>()?.as_str().into(),
+			/* The rasn BMPString decoder does not decode UTF-16 */
+			Tag::BMP_STRING => get_string_from_utf16_be(self.get_contents()?)?,
 			Tag::GENERAL_STRING => self.decode::<GeneralString>()?.as_str().into(),
 			Tag::IA5_STRING => self.decode::<Ia5String>()?.as_str().into(),
 			Tag::VISIBLE_STRING => self.decode::<VisibleString>()?.as_str().into(),
 			Tag::NUMERIC_STRING => self.decode::<NumericString>()?.as_str().into(),
 			Tag::UNIVERSAL_STRING => self.decode::<UniversalString>()?.as_str().into(),
 			Tag::UTF8_STRING => self.decode::<Utf8String>()?.as_str().into(),
+			/* TeletexString is decoded as Latin-1, as most encoders treat it */
+			Tag::TELETEX_STRING => get_string_from_latin1(self.get_contents()?),
 			_ => bail!(ASN1NAPIError::UnknownStringFormat),
-		})
+		};
+
+		if self.options.trim_nul.unwrap_or(false) {
+			Ok(value.trim_end_matches('\0').into())
+		} else {
+			Ok(value)
+		}
+	}
+
+	/// Get the kind of string to decode as, which may differ from the tag
+	/// based on the decode options.
+	pub(crate) fn get_string_kind(&self) -> &'static str {
+		match *self.get_tag() {
+			Tag::TELETEX_STRING if self.options.teletex_as_utf8.unwrap_or(false) => "utf8",
+			tag => get_string_kind_from_tag(tag),
+		}
 	}
 
 	/// Convert to a date.
@@ -256,6 +646,32 @@ impl ASN1Decoder {
 		get_utc_date_time_from_asn1_milli(&self.data)
 	}
 
+	/// Whether a date is the GeneralizedTime `99991231235959Z`, which a
+	/// certificate uses as its notAfter date to mean it does not expire.
+	#[napi]
+	pub fn is_no_expiry(&self) -> Result<bool> {
+		let time = self.get_time_string()?;
+
+		Ok(self.tag == Tag::GENERALIZED_TIME && time == ASN1_DATE_TIME_NO_EXPIRY)
+	}
+
+	/// Convert a date to the nanoseconds since the epoch, keeping the full
+	/// precision of the fraction of a second of a GeneralizedTime.
+	#[napi]
+	pub fn into_time_nanos(&self, env: Env) -> Result<JsBigInt> {
+		get_js_big_int_from_big_int(env, get_time_nanos_from_asn1(&self.data)?)
+	}
+
+	/// Convert to a date/time keeping the time zone offset.
+	pub(crate) fn get_date_time(&self) -> Result<DateTime<FixedOffset>> {
+		get_date_time_from_asn1(&self.data)
+	}
+
+	/// Convert to the time string as it appears in the ASN.1 data.
+	pub(crate) fn get_time_string(&self) -> Result<String> {
+		get_time_string_from_asn1(&self.data)
+	}
+
 	/// Convert to an byte array.
 	#[napi]
 	pub fn into_bytes(&self) -> Result<Vec<u8>> {
@@ -286,12 +702,359 @@ impl ASN1Decoder {
 		))
 	}
 
+	/// Get the number of unused bits declared by the leading octet of a BIT
+	/// STRING, without decoding the content.
+	#[napi]
+	pub fn bit_string_unused_bits(&self) -> Result<u32> {
+		if self.tag != Tag::BIT_STRING {
+			bail!(ASN1NAPIError::InvalidBitString);
+		}
+
+		match self.get_contents()?.first() {
+			Some(&unused_bits) if unused_bits < 8 => Ok(u32::from(unused_bits)),
+			_ => bail!(ASN1NAPIError::InvalidBitString),
+		}
+	}
+
+	/// Get a decoder for the DER structure encapsulated by a BIT STRING, such
+	/// as the ECDSA-Sig-Value of a signature, which must have no unused bits.
+	#[napi]
+	pub fn into_bit_string_encapsulated(&self) -> Result<ASN1Decoder> {
+		if self.bit_string_unused_bits()? != 0 {
+			bail!(ASN1NAPIError::InvalidBitString);
+		}
+
+		let contents = &self.get_contents()?[1..];
+
+		match split_elements(contents) {
+			Ok(elements) if elements.len() == 1 => Ok(Self::new_with_options(
+				contents.to_vec(),
+				self.options.clone(),
+			)),
+			_ => bail!(ASN1NAPIError::MalformedData),
+		}
+	}
+
 	/// Convert to an Context object.
 	#[napi]
 	pub fn into_context_tag(&self, env: Env) -> Result<ASN1ContextTag> {
 		get_js_context_tag_from_asn1_context(env, self.get_context()?)
 	}
 
+	/// Decode the element at a path of sequence indices and "contains" for
+	/// context tags, such as "0/5/contains/1".
+	#[napi(ts_return_type = "ASN1AnyJS")]
+	pub fn at(&self, env: Env, path: String) -> Result<JsUnknown> {
+		get_js_unknown_from_asn1_data(env, ASN1Data::try_from(self.get_at(path)?)?)
+	}
+
+	/// Find the entry of a SEQUENCE OF SEQUENCE { OID, ... }, such as
+	/// Extensions or attributes, whose first element is the OID and get the
+	/// raw encoding of its value, which is the last element of the entry.
+	pub(crate) fn find_value_by_oid<T: AsRef<str>>(&self, oid: T) -> Result<Option<Vec<u8>>> {
+		if self.tag != Tag::SEQUENCE {
+			bail!(ASN1NAPIError::MalformedData);
+		}
+
+		Ok(
+			get_entry_value_by_oid(&self.get_element_decoders()?, &get_oid_elements(oid)?)?
+				.map(|value| value.get_raw().to_vec()),
+		)
+	}
+
+	/// Find the entry of a SEQUENCE OF SEQUENCE { OID, ... } by its OID and
+	/// get the encoded value of the entry, or null if there is none.
+	#[napi(js_name = "findByOid")]
+	pub fn find_by_oid(&self, oid: String) -> Result<Option<Buffer>> {
+		Ok(self.find_value_by_oid(oid)?.map(Buffer::from))
+	}
+
+	/// Get the SigningTime attribute of the signed attributes of a CMS
+	/// SignerInfo, either as a SET OF Attribute or tagged as [0], or null
+	/// if there is none.
+	#[napi]
+	pub fn into_signing_time(&self) -> Result<Option<DateTime<Utc>>> {
+		if *self.get_tag() != Tag::SET
+			&& (*self.get_tag() != Tag::new(Class::Context, 0) || !self.is_constructed)
+		{
+			bail!(ASN1NAPIError::MalformedData);
+		}
+
+		match get_entry_value_by_oid(
+			&self.get_element_decoders()?,
+			&get_oid_elements("signingTime")?,
+		)? {
+			Some(values) if *values.get_tag() == Tag::SET => {
+				match values.get_element_decoders()?.as_slice() {
+					[time] => Ok(Some(time.into_date()?)),
+					_ => bail!(ASN1NAPIError::MalformedData),
+				}
+			}
+			Some(_) => bail!(ASN1NAPIError::MalformedData),
+			None => Ok(None),
+		}
+	}
+
+	/// Check if the data conforms to a schema describing the expected kinds
+	/// and nesting, such as `{ seq: ['integer', { oid: true }, { seq: '*' }] }`,
+	/// so that untrusted data can be rejected before it is processed.
+	#[napi]
+	pub fn matches_schema(
+		&self,
+		#[napi(ts_arg_type = "ASN1SchemaKind | Record<string, any>")] schema: JsUnknown,
+	) -> Result<bool> {
+		Ok(ASN1Schema::try_from(schema)?.matches(self))
+	}
+
+	/// Split the elements of a Sequence into the first elements and a decoder
+	/// for a Sequence of the remaining elements, so that the tail has the
+	/// same shape however many elements remain.
+	pub(crate) fn get_head_and_tail(
+		&self,
+		head_count: usize,
+	) -> Result<(Vec<ASN1Decoder>, Option<ASN1Decoder>)> {
+		if self.tag != Tag::SEQUENCE {
+			bail!(ASN1NAPIError::MalformedData);
+		}
+
+		let mut head = self.get_element_decoders()?;
+
+		if head_count > head.len() {
+			bail!(ASN1NAPIError::InvalidHeadCount);
+		}
+
+		let remaining = head.split_off(head_count);
+		let tail = match remaining.is_empty() {
+			true => None,
+			false => Some(Self::new_with_options(
+				writer::encode_tlv(
+					0x30,
+					&remaining
+						.iter()
+						.map(|element| element.get_raw())
+						.collect::<Vec<&[u8]>>()
+						.concat(),
+				)?,
+				self.options.clone(),
+			)),
+		};
+
+		Ok((head, tail))
+	}
+
+	/// Decode the first elements of a Sequence and get the remaining elements
+	/// as a decoder of a Sequence of those elements, such as for a trailing
+	/// ANY or fields which may be appended by later versions of a structure.
+	#[napi]
+	pub fn into_head_and_tail(&self, env: Env, head_count: u32) -> Result<ASN1HeadAndTail> {
+		let (head, tail) = self.get_head_and_tail(head_count as usize)?;
+
+		Ok(ASN1HeadAndTail {
+			head: get_js_array_from_asn_iter(env, head.into_iter().map(ASN1Data::try_from))?,
+			tail: tail.map_or(Either::B(Null), Either::A),
+		})
+	}
+
+	/// Decode the elements of a Sequence of a known shape, where the schema
+	/// lists the type of each element. Each element is decoded directly as
+	/// that type, and any mismatch is an error.
+	#[napi(ts_return_type = "any[]")]
+	pub fn into_schema(
+		&self,
+		env: Env,
+		#[napi(ts_arg_type = "ASN1SchemaKind[]")] schema: Vec<String>,
+	) -> Result<Array> {
+		let elements = self.get_element_decoders()?;
+
+		if self.tag != Tag::SEQUENCE || elements.len() != schema.len() {
+			bail!(ASN1NAPIError::InvalidSchema);
+		}
+
+		let mut array = env.create_array(elements.len() as u32)?;
+
+		for (index, (element, kind)) in elements.iter().zip(schema).enumerate() {
+			array.set(index as u32, element.get_schema_value(env, &kind)?)?;
+		}
+
+		Ok(array)
+	}
+
+	/// Flatten into a depth-first list of the primitive elements, each with
+	/// the path to the element, its type and its value.
+	#[napi]
+	pub fn flatten(&self, env: Env) -> Result<Vec<ASN1FlatEntry>> {
+		let mut entries = Vec::new();
+
+		self.flatten_into(env, String::new(), &mut entries)?;
+
+		Ok(entries)
+	}
+
+	/// Convert into nested arrays of the raw TLV of each primitive element,
+	/// where each SEQUENCE, SET or explicit context tag is an array of its
+	/// elements. The encoding of every element is kept exactly.
+	#[napi(ts_return_type = "Buffer | any[]")]
+	pub fn into_raw_tree(&self, env: Env) -> Result<JsUnknown> {
+		if matches!(self.tag, Tag::SEQUENCE | Tag::SET)
+			|| (self.tag.class == Class::Context && self.is_constructed)
+		{
+			let elements = self.get_element_decoders()?;
+			let mut array = env.create_array(elements.len() as u32)?;
+
+			for (index, element) in elements.iter().enumerate() {
+				array.set(index as u32, element.into_raw_tree(env)?)?;
+			}
+
+			Ok(array.coerce_to_object()?.into_unknown())
+		} else {
+			Ok(env
+				.create_buffer_with_data(self.data.clone())?
+				.into_raw()
+				.into_unknown())
+		}
+	}
+
+	/// Get a compact description of the structure from the tags of the
+	/// element and its elements, such as "seq(int,oid,seq,set)", without
+	/// decoding any values.
+	#[napi]
+	pub fn shape_fingerprint(&self) -> Result<String> {
+		let name = self.get_shape_name()?;
+
+		if matches!(self.tag, Tag::SEQUENCE | Tag::SET)
+			|| (self.tag.class == Class::Context && self.is_constructed)
+		{
+			let names = self
+				.get_element_decoders()?
+				.iter()
+				.map(ASN1Decoder::get_shape_name)
+				.collect::<Result<Vec<String>>>()?;
+
+			Ok(format!("{}({})", name, names.join(",")))
+		} else {
+			Ok(name)
+		}
+	}
+
+	/// Get the TBSCertificate of a certificate exactly as it was encoded, for
+	/// verifying the signature. The bytes are never re-encoded.
+	#[napi]
+	pub fn tbs_certificate_bytes(&self) -> Result<Buffer> {
+		match self.get_element_decoders()?.first() {
+			Some(tbs) if self.tag == Tag::SEQUENCE && *tbs.get_tag() == Tag::SEQUENCE => {
+				Ok(tbs.get_raw().to_vec().into())
+			}
+			_ => bail!(ASN1NAPIError::InvalidCertificate),
+		}
+	}
+
+	/// Convert a SEQUENCE OF Certificate or concatenated certificates to an
+	/// array of certificate objects.
+	#[napi]
+	pub fn into_certificate_chain(&self, env: Env) -> Result<Vec<ASN1Certificate>> {
+		get_certificate_chain(self)?
+			.into_iter()
+			.map(|certificate| certificate.into_js(env))
+			.collect()
+	}
+
+	/// Convert a certificate policies extension value to an array of policy
+	/// objects with the policy OID and the array of qualifiers.
+	#[napi]
+	pub fn into_certificate_policies(&self, env: Env) -> Result<Vec<ASN1PolicyInformation>> {
+		get_certificate_policies(env, self)
+	}
+
+	/// Convert a CRL distribution points extension value to an array of
+	/// distribution point objects with the URLs of their full names.
+	#[napi]
+	pub fn into_crl_distribution_points(&self) -> Result<Vec<ASN1DistributionPoint>> {
+		get_crl_distribution_points(self)
+	}
+
+	/// Convert an authority information access extension value to an array
+	/// of access description objects with the method OID and URI location.
+	#[napi]
+	pub fn into_authority_info_access(&self) -> Result<Vec<ASN1AccessDescription>> {
+		get_authority_info_access(self)
+	}
+
+	/// Convert the extensions of a certificate, of Extensions or of a single
+	/// Extension to an array of extension objects.
+	#[napi]
+	pub fn into_extensions(&self) -> Result<Vec<ASN1Extension>> {
+		get_extensions(self)
+	}
+
+	/// Check if the keyIdentifier of the authority key identifier extension of
+	/// a certificate is the subject key identifier of an issuer, for linking
+	/// a certificate to its issuer when building a chain.
+	#[napi]
+	pub fn authority_key_id_matches(&self, issuer_ski: Buffer) -> Result<bool> {
+		get_authority_key_id_matches(self, &issuer_ski)
+	}
+
+	/// Decode the GeneralNames of the subject alternative name extension of a
+	/// certificate, or the GeneralNames themselves.
+	#[napi]
+	pub fn into_general_names(
+		&self,
+		options: Option<ASN1GeneralNamesOptions>,
+	) -> Result<Vec<ASN1GeneralName>> {
+		get_general_names(self.get_general_names()?, options)
+	}
+
+	/// Convert a PKCS attribute to an object with the attribute type and the
+	/// array of values.
+	#[napi]
+	pub fn into_attribute(&self, env: Env) -> Result<ASN1Attribute> {
+		get_attribute(env, self)
+	}
+
+	/// Find the Microsoft User Principal Name otherName in a certificate or
+	/// in the GeneralNames of a subject alternative name extension.
+	#[napi(js_name = "intoUserPrincipalName")]
+	pub fn into_user_principal_name(&self) -> Result<Option<String>> {
+		for name in self.get_general_names()? {
+			// otherName is [0] IMPLICIT SEQUENCE { type-id, [0] EXPLICIT value }
+			if *name.get_tag() != Tag::new(Class::Context, 0) || !name.is_constructed {
+				continue;
+			}
+
+			let elements = name.get_element_decoders()?;
+
+			if let [oid, value] = elements.as_slice() {
+				if oid.decode::<ObjectIdentifier>()?.to_vec()
+					== get_oid_elements("userPrincipalName")?
+				{
+					if let Some(value) = value.get_element_decoders()?.first() {
+						return Ok(Some(value.into_string()?));
+					}
+				}
+			}
+		}
+
+		Ok(None)
+	}
+
+	/// Get the raw tag octet(s) of the top-level element, such as `5f8768`
+	/// for a primitive `[APPLICATION 1000]`.
+	#[napi]
+	pub fn tag_bytes(&self) -> Result<Buffer> {
+		Ok(self.get_tag_bytes()?.to_vec().into())
+	}
+
+	/// Whether a context tag was encoded as constructed, which is used to
+	/// infer explicit rather than implicit tagging.
+	#[napi]
+	pub fn context_tag_is_constructed(&self) -> Result<bool> {
+		if self.tag.class != Class::Context {
+			bail!(ASN1NAPIError::UknownContext);
+		}
+
+		Ok(self.is_constructed)
+	}
+
 	/// Convert a Sequence to an Array.
 	#[napi(ts_return_type = "any[]")]
 	pub fn into_array(&self, env: Env) -> Result<Array> {
@@ -305,7 +1068,10 @@ impl Iterator for ASN1Iterator {
 	fn next(&mut self) -> Option<Self::Item> {
 		if let Some(item) = self.sequence.get(self.index) {
 			self.index += 1;
-			Some(ASN1Data::try_from(ASN1Decoder::new(item.as_bytes().into())))
+			Some(ASN1Data::try_from(ASN1Decoder::new_with_options(
+				item.as_bytes().into(),
+				self.options.clone(),
+			)))
 		} else {
 			None
 		}
@@ -318,7 +1084,7 @@ impl IntoIterator for ASN1Decoder {
 	type IntoIter = ASN1Iterator;
 
 	fn into_iter(self) -> Self::IntoIter {
-		ASN1Iterator::from(self.decode::<Vec<Any>>().unwrap_or_default())
+		ASN1Iterator::from(self.decode::<Vec<Any>>().unwrap_or_default()).with_options(self.options)
 	}
 }
 
@@ -328,6 +1094,7 @@ impl From<Vec<Any>> for ASN1Iterator {
 			length: sequence.len(),
 			index: 0,
 			sequence,
+			options: get_decode_options(),
 		}
 	}
 }
@@ -371,6 +1138,54 @@ impl TryFrom<Vec<u8>> for ASN1Decoder {
 	}
 }
 
+/// Get the value, the last element, of the first SEQUENCE { OID, ... }
+/// entry with an OID among the entries.
+pub(crate) fn get_entry_value_by_oid(
+	entries: &[ASN1Decoder],
+	oid: &[u32],
+) -> Result<Option<ASN1Decoder>> {
+	for entry in entries {
+		if *entry.get_tag() != Tag::SEQUENCE {
+			continue;
+		}
+
+		if let [first, .., value] = entry.get_element_decoders()?.as_slice() {
+			if *first.get_tag() == Tag::OBJECT_IDENTIFIER
+				&& first.decode::<ObjectIdentifier>()?.to_vec() == oid
+			{
+				return Ok(Some(value.clone()));
+			}
+		}
+	}
+
+	Ok(None)
+}
+
+/// Check if data is a constructed OCTET STRING whose contents split into
+/// OCTET STRING segments.
+fn is_segmented_octet_string(data: &[u8]) -> bool {
+	if data.first() != Some(&0x24) {
+		return false;
+	}
+
+	let contents = match (header_length(data), content_length(data)) {
+		(Ok(header), Ok(length)) => header
+			.checked_add(length)
+			.and_then(|end| data.get(header..end)),
+		_ => None,
+	};
+
+	match contents.map(split_elements) {
+		Some(Ok(segments)) => {
+			!segments.is_empty()
+				&& segments
+					.iter()
+					.all(|segment| matches!(segment.first(), Some(0x04 | 0x24)))
+		}
+		_ => false,
+	}
+}
+
 #[cfg(test)]
 mod test {
 	use std::collections::VecDeque;
@@ -378,10 +1193,12 @@ mod test {
 
 	use chrono::{DateTime, FixedOffset, TimeZone, Utc};
 	use num_bigint::BigInt;
-	use rasn::types::BitString;
+	use rasn::types::{Any, BitString};
 
 	use crate::asn1::*;
+	use crate::certificate::{get_authority_key_id_matches, Certificate};
 	use crate::objects::*;
+	use crate::options::*;
 	use crate::types::*;
 	use crate::*;
 
@@ -437,9 +1254,12 @@ mod test {
 			ASN1Data::Integer(0),
 			ASN1Data::Integer(456),
 			ASN1Data::Integer(123),
-			ASN1Data::GeneralizedTime(DateTime::<FixedOffset>::from(
-				Utc.timestamp_millis_opt(1655921880210).unwrap(),
-			)),
+			ASN1Data::Time {
+				instant: DateTime::<FixedOffset>::from(
+					Utc.timestamp_millis_opt(1655921880210).unwrap(),
+				),
+				kind: ASN1TimeKind::General,
+			},
 			ASN1Data::Bytes(
 				hex::decode(
 					"0002C4FD23DEAEBBA3CAC51E2597AD8A5BBAD1578E6\
@@ -508,12 +1328,8 @@ mod test {
 					))),
 				]),
 				ASN1Data::Array(vec![
-					ASN1Data::UtcTime(DateTime::<Utc>::from(
-						Utc.with_ymd_and_hms(2022, 11, 03, 1, 29, 58).unwrap(),
-					)),
-					ASN1Data::UtcTime(DateTime::<Utc>::from(
-						Utc.with_ymd_and_hms(2027, 05, 11, 1, 29, 58).unwrap(),
-					)),
+					ASN1Data::new_time(Utc.with_ymd_and_hms(2022, 11, 3, 1, 29, 58).unwrap()),
+					ASN1Data::new_time(Utc.with_ymd_and_hms(2027, 5, 11, 1, 29, 58).unwrap()),
 				]),
 				ASN1Data::Array(vec![
 					ASN1Data::Object(ASN1Object::Set(ASN1Set::new(
@@ -568,7 +1384,9 @@ mod test {
 							]),
 						]),
 						ASN1Data::Array(vec![
-							ASN1Data::Object(ASN1Object::Oid(ASN1OID::new("2.5.29.35"))),
+							ASN1Data::Object(ASN1Object::Oid(ASN1OID::new(
+								"authorityKeyIdentifier",
+							))),
 							ASN1Data::Bytes(vec![
 								0x30, 0x16, 0x80, 0x14, 0x7A, 0xA9, 0x96, 0x96, 0x0F, 0x66, 0x75,
 								0x09, 0xD7, 0x0E, 0xD1, 0x62, 0x57, 0xCB, 0x97, 0x82, 0x20, 0x82,
@@ -636,6 +1454,197 @@ mod test {
 		);
 	}
 
+	#[test]
+	fn test_asn1_serial_number_round_trip() {
+		for encoded in ["020300ffff", "020900ffffffffffffffff", "02020080"] {
+			let data = hex::decode(encoded).expect("hex");
+			let value = ASN1Data::try_from(ASN1Decoder::new(data.clone())).unwrap();
+
+			assert_eq!(ASN1Encoder::new(value).encode().unwrap(), data);
+		}
+	}
+
+	#[test]
+	fn test_asn1_positive_big_int_leading_zero() {
+		for (value, encoded) in [
+			(0xffff, "020300ffff"),
+			(0x80, "02020080"),
+			(-0x80, "020180"),
+		] {
+			let data = ASN1Data::BigInt(BigInt::from(value));
+
+			assert_eq!(
+				hex::encode(ASN1Encoder::new(data).encode().unwrap()),
+				encoded
+			);
+		}
+	}
+
+	#[test]
+	fn test_asn1_get_unsigned_bytes_le() {
+		assert_eq!(
+			ASN1Decoder::new(vec![0x02, 0x02, 0x01, 0x00])
+				.get_unsigned_bytes_le()
+				.unwrap(),
+			vec![0x00, 0x01]
+		);
+		assert_eq!(
+			ASN1Decoder::new(vec![0x02, 0x03, 0x00, 0x80, 0x01])
+				.get_unsigned_bytes_le()
+				.unwrap(),
+			vec![0x01, 0x80]
+		);
+		assert_eq!(
+			ASN1Decoder::new(vec![0x02, 0x01, 0x00])
+				.get_unsigned_bytes_le()
+				.unwrap(),
+			vec![0x00]
+		);
+		assert!(ASN1Decoder::new(vec![0x02, 0x01, 0xff])
+			.get_unsigned_bytes_le()
+			.is_err());
+	}
+
+	#[test]
+	fn test_asn1_into_bit_string_encapsulated() {
+		let obj = ASN1Decoder::from_base64(TEST_CERT.into()).expect("base64");
+		let signature = obj
+			.get_at("2")
+			.unwrap()
+			.into_bit_string_encapsulated()
+			.unwrap();
+		let elements = signature.get_element_decoders().unwrap();
+
+		// ECDSA-Sig-Value is a SEQUENCE of the r and s INTEGERs
+		assert_eq!(*signature.get_tag(), Tag::SEQUENCE);
+		assert_eq!(elements.len(), 2);
+		assert!(elements
+			.iter()
+			.all(|element| *element.get_tag() == Tag::INTEGER));
+		assert!(ASN1Decoder::new(vec![0x03, 0x03, 0x01, 0x05, 0x00])
+			.into_bit_string_encapsulated()
+			.is_err());
+		assert!(ASN1Decoder::new(vec![0x03, 0x04, 0x00, 0x05, 0x00, 0x00])
+			.into_bit_string_encapsulated()
+			.is_err());
+		assert!(ASN1Decoder::new(vec![0x04, 0x03, 0x00, 0x05, 0x00])
+			.into_bit_string_encapsulated()
+			.is_err());
+	}
+
+	#[test]
+	fn test_asn1_get_big_ints() {
+		let values = vec![
+			BigInt::from(u64::MAX) + 1,
+			-(BigInt::from(1) << 200_u32),
+			BigInt::from(42),
+		];
+		let data = ASN1Data::Array(values.iter().cloned().map(ASN1Data::BigInt).collect());
+		let obj = ASN1Decoder::new(ASN1Encoder::new(data).encode().unwrap());
+
+		assert_eq!(obj.get_big_ints().unwrap(), values);
+		assert!(ASN1Decoder::new(vec![0x30, 0x03, 0x01, 0x01, 0xff])
+			.get_big_ints()
+			.is_err());
+		assert!(ASN1Decoder::new(vec![0x31, 0x03, 0x02, 0x01, 0x01])
+			.get_big_ints()
+			.is_err());
+	}
+
+	#[test]
+	fn test_asn1_shape_fingerprint() {
+		let block = ASN1Decoder::from_base64(TEST_BLOCK.into()).expect("base64");
+		let vote = ASN1Decoder::from_base64(TEST_VOTE.into()).expect("base64");
+
+		assert_eq!(
+			block.shape_fingerprint().unwrap(),
+			"seq(int,int,int,date,bytes,bytes,seq,int)"
+		);
+		assert_eq!(vote.shape_fingerprint().unwrap(), "seq(oid,seq)");
+		assert_eq!(
+			ASN1Decoder::new(vec![0xa1, 0x05, 0x5f, 0x87, 0x68, 0x01, 0x00])
+				.shape_fingerprint()
+				.unwrap(),
+			"ctx1(5f8768)"
+		);
+		assert_eq!(
+			ASN1Decoder::new(vec![0x0c, 0x01, 0x41])
+				.shape_fingerprint()
+				.unwrap(),
+			"str"
+		);
+		assert!(ASN1Decoder::new(vec![0x30, 0x03, 0x02, 0x01])
+			.shape_fingerprint()
+			.is_err());
+	}
+
+	#[test]
+	fn test_asn1_get_tag_bytes() {
+		// [APPLICATION 1000] with empty contents
+		assert_eq!(
+			ASN1Decoder::new(vec![0x5f, 0x87, 0x68, 0x00])
+				.get_tag_bytes()
+				.unwrap(),
+			&[0x5f, 0x87, 0x68]
+		);
+		assert_eq!(
+			ASN1Decoder::new(vec![0x02, 0x01, 0x01])
+				.get_tag_bytes()
+				.unwrap(),
+			&[0x02]
+		);
+		assert!(ASN1Decoder::new(vec![0x7f, 0x87]).get_tag_bytes().is_err());
+	}
+
+	#[test]
+	fn test_asn1_bit_string_unused_bits() {
+		assert_eq!(
+			ASN1Decoder::new(vec![0x03, 0x02, 0x05, 0xa0])
+				.bit_string_unused_bits()
+				.unwrap(),
+			5
+		);
+		assert_eq!(
+			ASN1Decoder::new(vec![0x03, 0x01, 0x00])
+				.bit_string_unused_bits()
+				.unwrap(),
+			0
+		);
+		assert!(ASN1Decoder::new(vec![0x03, 0x00])
+			.bit_string_unused_bits()
+			.is_err());
+		assert!(ASN1Decoder::new(vec![0x03, 0x02, 0x08, 0x00])
+			.bit_string_unused_bits()
+			.is_err());
+		assert!(ASN1Decoder::new(vec![0x04, 0x02, 0x05, 0xa0])
+			.bit_string_unused_bits()
+			.is_err());
+	}
+
+	#[test]
+	fn test_asn1_into_null() {
+		assert!(ASN1Decoder::new(vec![0x05, 0x00]).into_null().is_ok());
+		assert!(ASN1Decoder::new(vec![0x02, 0x01, 0x00])
+			.into_null()
+			.is_err());
+
+		// AlgorithmIdentifier with NULL parameters
+		let data = hex::decode("300d06092a864886f70d01010b0500").expect("hex");
+		let obj = ASN1Decoder::new(data);
+
+		assert_eq!(
+			ASN1Data::try_from(obj.get_at("1").unwrap()).unwrap(),
+			ASN1Data::Null
+		);
+		assert_eq!(
+			ASN1Data::try_from(obj).unwrap(),
+			ASN1Data::Array(vec![
+				ASN1Data::Object(ASN1Object::Oid(ASN1OID::new("1.2.840.113549.1.1.11"))),
+				ASN1Data::Null,
+			])
+		);
+	}
+
 	#[test]
 	fn test_asn1_into_string() {
 		let encoded = "EwR0ZXN0";
@@ -674,6 +1683,124 @@ mod test {
 		assert_eq!(obj.into_oid().unwrap(), ASN1OID::new("sha256"));
 	}
 
+	#[test]
+	fn test_asn1_unknown_round_trip() {
+		for input in [
+			// ENUMERATED
+			"0a0105",
+			// Constructed EXTERNAL
+			"28050201010500",
+			// Constructed EXTERNAL with an indefinite length
+			"28800201010000",
+			// High tag number form
+			"1f2203010203",
+		] {
+			let data = hex::decode(input).expect("hex");
+			let result = ASN1Data::try_from(ASN1Decoder::new(data.clone())).unwrap();
+
+			assert_eq!(result, ASN1Data::Unknown(Any::new(data.clone())));
+			assert_eq!(ASN1Encoder::new(result.clone()).encode().unwrap(), data);
+
+			let sequence = ASN1Data::Array(vec![result.clone(), ASN1Data::Integer(1)]);
+			let mut expected = vec![0x30, data.len() as u8 + 3];
+			expected.extend_from_slice(&data);
+			expected.extend_from_slice(&[0x02, 0x01, 0x01]);
+
+			assert_eq!(ASN1Encoder::new(sequence).encode().unwrap(), expected);
+
+			let set = ASN1Data::Set(vec![result]);
+			let mut expected = vec![0x31, data.len() as u8];
+			expected.extend_from_slice(&data);
+
+			assert_eq!(ASN1Encoder::new(set).encode().unwrap(), expected);
+		}
+	}
+
+	#[test]
+	fn test_asn1_into_oid_lenient() {
+		let data = vec![0x26, 0x03, 0x55, 0x04, 0x03];
+		let options = ASN1DecodeOptions {
+			lenient: Some(true),
+			..Default::default()
+		};
+
+		assert_eq!(
+			ASN1Data::try_from(ASN1Decoder::new(data.clone())).unwrap(),
+			ASN1Data::Unknown(Any::new(data.clone()))
+		);
+		assert_eq!(
+			ASN1Data::try_from(ASN1Decoder::new_with_options(data, options)).unwrap(),
+			ASN1Data::Object(ASN1Object::Oid(ASN1OID::new("commonName")))
+		);
+	}
+
+	#[test]
+	fn test_asn1_segmented_octet_string_lenient() {
+		let options = ASN1DecodeOptions {
+			lenient: Some(true),
+			..Default::default()
+		};
+		let segmented = vec![0x24, 0x06, 0x04, 0x01, 0x61, 0x04, 0x01, 0x62];
+		let decoder = ASN1Decoder::new_with_options(segmented.clone(), options.clone());
+
+		assert!(decoder.get_is_constructed());
+		assert_eq!(decoder.get_raw(), segmented);
+
+		let decoder = ASN1Decoder::new_with_options(vec![0x24, 0x02, 0x61, 0x62], options);
+
+		assert!(!decoder.get_is_constructed());
+		assert_eq!(decoder.into_bytes().unwrap(), b"ab");
+	}
+
+	#[test]
+	fn test_asn1_into_string_trim_nul() {
+		let input = vec![0x13, 0x06, 0x54, 0x65, 0x73, 0x74, 0x00, 0x00];
+		let options = ASN1DecodeOptions {
+			trim_nul: Some(true),
+			..Default::default()
+		};
+
+		assert_eq!(
+			ASN1Decoder::new(input.clone()).into_string().unwrap(),
+			"Test\0\0"
+		);
+		assert_eq!(
+			ASN1Decoder::new_with_options(input, options)
+				.into_string()
+				.unwrap(),
+			"Test"
+		);
+	}
+
+	#[test]
+	fn test_asn1_max_value_length() {
+		let options = ASN1DecodeOptions {
+			max_value_length: Some(4),
+			..Default::default()
+		};
+		let decode = |data: Vec<u8>| {
+			ASN1Data::try_from(ASN1Decoder::new_with_options(data, options.clone()))
+				.map_err(|error| error.downcast::<ASN1NAPIError>().unwrap())
+		};
+
+		// A declared 1GB OCTET STRING with truncated contents
+		let truncated = vec![0x04, 0x84, 0x40, 0x00, 0x00, 0x00, 0x01, 0x02];
+
+		assert_eq!(decode(truncated), Err(ASN1NAPIError::InvalidValueLength));
+		assert_eq!(
+			decode(vec![0x13, 0x05, 0x54, 0x65, 0x73, 0x74, 0x73]),
+			Err(ASN1NAPIError::InvalidValueLength)
+		);
+		assert_eq!(
+			decode(vec![0x30, 0x06, 0x04, 0x04, 0x01, 0x02, 0x03, 0x04]).unwrap(),
+			ASN1Data::Array(vec![ASN1Data::Bytes(vec![0x01, 0x02, 0x03, 0x04])])
+		);
+		assert_eq!(
+			decode(vec![0x30, 0x07, 0x03, 0x05, 0x00, 0x01, 0x02, 0x03, 0x04]),
+			Err(ASN1NAPIError::InvalidValueLength)
+		);
+	}
+
 	#[test]
 	fn test_asn1_into_bit_string() {
 		let encoded = "AwYAChAUIAk=";
@@ -736,6 +1863,20 @@ mod test {
 		);
 	}
 
+	#[test]
+	fn test_asn1_empty_context_tag() {
+		let obj = ASN1Decoder::new(vec![0xa0, 0x00]);
+		let context = ASN1Context::new(0, ASN1Data::Undefined, "explicit");
+
+		assert_eq!(obj.get_context().unwrap(), context);
+		assert_eq!(
+			ASN1Encoder::new(ASN1Data::Object(ASN1Object::Context(context)))
+				.encode()
+				.unwrap(),
+			vec![0xa0, 0x00]
+		);
+	}
+
 	#[test]
 	fn test_asn1_block_into_sequence() {
 		let block = fixture_get_test_block();
@@ -784,6 +1925,187 @@ mod test {
 		assert_eq!(sig, test_sig);
 	}
 
+	#[test]
+	fn test_asn1_cert_into_certificate() {
+		let obj = ASN1Decoder::from_base64(TEST_CERT.into()).expect("base64");
+		let certificate = Certificate::try_from(&obj).unwrap();
+		let test_tbs = cast_data!(fixture_get_test_cert().remove(0), ASN1Data::Array);
+
+		assert_eq!(certificate.version, 3);
+		assert_eq!(certificate.serial, BigInt::from(1));
+		assert_eq!(certificate.algorithm, ASN1OID::new("sha256WithEcDSA"));
+		assert_eq!(certificate.issuer, test_tbs[3]);
+		assert_eq!(certificate.subject, test_tbs[5]);
+		assert_eq!(certificate.encode().unwrap(), obj.get_raw());
+	}
+
+	#[test]
+	fn test_asn1_cert_into_certificate_version_overflow() {
+		let obj = ASN1Decoder::from_base64(TEST_CERT.into()).expect("base64");
+		let raw = |element: ASN1Decoder| ASN1Data::Unknown(Any::new(element.get_raw().to_vec()));
+		let mut elements = obj.get_element_decoders().unwrap();
+		let mut tbs = elements
+			.remove(0)
+			.get_element_decoders()
+			.unwrap()
+			.into_iter()
+			.map(raw)
+			.collect::<Vec<ASN1Data>>();
+
+		// [0] EXPLICIT INTEGER of the largest i64 version
+		tbs[0] = ASN1Data::Object(ASN1Object::Context(ASN1Context::new(
+			0,
+			ASN1Data::Integer(i64::MAX),
+			"explicit",
+		)));
+
+		let mut certificate = vec![ASN1Data::Array(tbs)];
+		certificate.extend(elements.into_iter().map(raw));
+		let obj = ASN1Decoder::new(
+			ASN1Encoder::new(ASN1Data::Array(certificate))
+				.encode()
+				.unwrap(),
+		);
+
+		assert!(Certificate::try_from(&obj).is_err());
+	}
+
+	#[test]
+	fn test_asn1_authority_key_id_matches() {
+		let obj = ASN1Decoder::from_base64(TEST_CERT.into()).expect("base64");
+		let issuer_ski = hex::decode("7aa996960f667509d70ed16257cb97822082f32b").expect("hex");
+		let subject_ski = hex::decode("18aaadccbb92348082e2121d15cddaed07489213").expect("hex");
+
+		assert!(get_authority_key_id_matches(&obj, &issuer_ski).unwrap());
+		assert!(!get_authority_key_id_matches(&obj, &subject_ski).unwrap());
+
+		let mut certificate = Certificate::try_from(&obj).unwrap();
+		certificate.extensions = None;
+		let obj = ASN1Decoder::new(certificate.encode().unwrap());
+
+		assert!(!get_authority_key_id_matches(&obj, &issuer_ski).unwrap());
+	}
+
+	#[test]
+	fn test_asn1_find_value_by_oid() {
+		let obj = ASN1Decoder::from_base64(TEST_CERT.into()).expect("base64");
+		let extensions = obj.get_at("0/7/contains").unwrap();
+
+		// BasicConstraints with an empty SEQUENCE in an OCTET STRING
+		assert_eq!(
+			extensions.find_value_by_oid("2.5.29.19").unwrap(),
+			Some(vec![0x04, 0x02, 0x30, 0x00])
+		);
+		assert_eq!(extensions.find_value_by_oid("2.5.29.17").unwrap(), None);
+		assert!(obj
+			.get_at("0/0")
+			.unwrap()
+			.find_value_by_oid("2.5.29.19")
+			.is_err());
+	}
+
+	#[test]
+	fn test_asn1_get_head_and_tail() {
+		let obj = ASN1Decoder::new(vec![
+			0x30, 0x09, 0x02, 0x01, 0x01, 0x02, 0x01, 0x02, 0x04, 0x01, 0x03,
+		]);
+
+		let (head, tail) = obj.get_head_and_tail(2).unwrap();
+
+		assert_eq!(head.len(), 2);
+		assert_eq!(head[1].into_integer().unwrap(), 2);
+		assert_eq!(tail.unwrap().get_raw(), [0x30, 0x03, 0x04, 0x01, 0x03]);
+
+		let (head, tail) = obj.get_head_and_tail(1).unwrap();
+
+		assert_eq!(head.len(), 1);
+		assert_eq!(
+			tail.unwrap().get_raw(),
+			[0x30, 0x06, 0x02, 0x01, 0x02, 0x04, 0x01, 0x03]
+		);
+
+		let (head, tail) = obj.get_head_and_tail(3).unwrap();
+
+		assert_eq!(head.len(), 3);
+		assert!(tail.is_none());
+		assert!(obj.get_head_and_tail(4).is_err());
+		assert!(ASN1Decoder::new(vec![0x02, 0x01, 0x01])
+			.get_head_and_tail(0)
+			.is_err());
+
+		// A single trailing Sequence is wrapped like several elements
+		let nested = ASN1Decoder::new(vec![0x30, 0x07, 0x02, 0x01, 0x01, 0x30, 0x02, 0x05, 0x00]);
+		let (_, tail) = nested.get_head_and_tail(1).unwrap();
+		let tail = tail.unwrap().get_element_decoders().unwrap();
+
+		assert_eq!(tail.len(), 1);
+		assert_eq!(tail[0].get_raw(), [0x30, 0x02, 0x05, 0x00]);
+	}
+
+	#[test]
+	fn test_asn1_is_no_expiry() {
+		let obj = ASN1Decoder::new(hex::decode("180f39393939313233313233353935395a").expect("hex"));
+
+		assert!(obj.is_no_expiry().unwrap());
+		assert!(!ASN1Decoder::from_base64(TEST_CERT.into())
+			.expect("base64")
+			.get_at("0/4/1")
+			.unwrap()
+			.is_no_expiry()
+			.unwrap());
+		assert!(ASN1Decoder::new(vec![0x02, 0x01, 0x01])
+			.is_no_expiry()
+			.is_err());
+	}
+
+	#[test]
+	fn test_asn1_into_signing_time() {
+		// ContentType and SigningTime 220926100000Z attributes
+		let attributes = "38301806092a864886f70d010903310b06092a864886f70d010701301c06092a\
+			 864886f70d010905310f170d3232303932363130303030305a";
+		let date = Utc.with_ymd_and_hms(2022, 9, 26, 10, 0, 0).unwrap();
+
+		for tag in ["31", "a0"] {
+			let obj = ASN1Decoder::new(hex::decode(format!("{}{}", tag, attributes)).expect("hex"));
+
+			assert_eq!(obj.into_signing_time().unwrap(), Some(date));
+		}
+
+		let obj = ASN1Decoder::new(
+			hex::decode("311a301806092a864886f70d010903310b06092a864886f70d010701").expect("hex"),
+		);
+
+		assert_eq!(obj.into_signing_time().unwrap(), None);
+		assert!(ASN1Decoder::new(vec![0x30, 0x00])
+			.into_signing_time()
+			.is_err());
+	}
+
+	#[test]
+	fn test_asn1_cert_at() {
+		let obj = ASN1Decoder::from_base64(TEST_CERT.into()).expect("base64");
+
+		assert_eq!(
+			ASN1Data::try_from(obj.get_at("0/5/4").unwrap()).unwrap(),
+			ASN1Data::Object(ASN1Object::Set(ASN1Set::new(
+				ASN1OID::new("commonName"),
+				ASN1String::new("client1.node1.keeta.com".to_string(), Some("utf8".into())),
+			)))
+		);
+		assert_eq!(
+			obj.get_at("0/5/4/0/1").unwrap().into_string().unwrap(),
+			"client1.node1.keeta.com"
+		);
+		assert_eq!(
+			ASN1Data::try_from(obj.get_at("0/0/contains").unwrap()).unwrap(),
+			ASN1Data::Integer(2)
+		);
+		assert!(obj.get_at("0/1/0").is_err());
+		assert!(obj.get_at("0/9").is_err());
+		assert!(obj.get_at("0/contains").is_err());
+		assert!(obj.get_at("0/name").is_err());
+	}
+
 	#[test]
 	fn test_asn1_encoder_to_base64() {
 		let block = fixture_get_test_block();
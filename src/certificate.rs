@@ -0,0 +1,764 @@
+use std::{iter::Peekable, net::IpAddr};
+
+use anyhow::{bail, Error, Result};
+use chrono::{DateTime, Utc};
+use napi::{
+	bindgen_prelude::{Array, Buffer, Either},
+	Env, JsBigInt, JsUnknown, Task,
+};
+use num_bigint::BigInt;
+use rasn::types::{Any, BitString, Class, Tag};
+
+use crate::{
+	asn1::{get_entry_value_by_oid, ASN1Decoder},
+	dn::get_rdns_from_asn1,
+	get_js_array_from_asn_iter, get_js_big_int_from_big_int, get_js_unknown_from_asn1_data,
+	objects::{get_oid_elements, ASN1Context, ASN1Object, ASN1RawBitString, ASN1OID},
+	types::ASN1Data,
+	utils::{get_big_int_from_js, get_unicode_domain, is_ia5_string, split_elements},
+	writer, ASN1NAPIError,
+};
+
+/// Fields of an X.509 certificate.
+#[napi(object, js_name = "ASN1Certificate")]
+pub struct ASN1Certificate {
+	/// Certificate version, such as 3 for v3.
+	pub version: u32,
+	pub serial: JsBigInt,
+	/// Signature algorithm OID name or identifier string.
+	pub algorithm: String,
+	#[napi(ts_type = "ASN1AnyJS")]
+	pub issuer: JsUnknown,
+	pub not_before: DateTime<Utc>,
+	pub not_after: DateTime<Utc>,
+	#[napi(ts_type = "ASN1AnyJS")]
+	pub subject: JsUnknown,
+	/// DER encoded SubjectPublicKeyInfo.
+	pub public_key: Buffer,
+	/// Issuer unique identifier bits, present only in v2 and v3 certificates.
+	pub issuer_unique_id: Option<Buffer>,
+	/// Subject unique identifier bits, present only in v2 and v3 certificates.
+	pub subject_unique_id: Option<Buffer>,
+	pub signature: Buffer,
+}
+
+/// Fields used to build an unsigned certificate for testing.
+#[napi(object, js_name = "ASN1TestCertificateOptions")]
+pub struct ASN1TestCertificateOptions {
+	#[napi(ts_type = "ASN1AnyJS")]
+	pub subject: JsUnknown,
+	#[napi(ts_type = "ASN1AnyJS")]
+	pub issuer: JsUnknown,
+	pub not_before: DateTime<Utc>,
+	pub not_after: DateTime<Utc>,
+	pub serial: JsBigInt,
+	/// DER encoded SubjectPublicKeyInfo.
+	pub public_key: Buffer,
+	/// Signature algorithm OID name or identifier string.
+	pub algorithm: String,
+}
+
+/// X.509 certificate extension.
+#[napi(object, js_name = "ASN1Extension")]
+#[derive(Clone)]
+pub struct ASN1Extension {
+	/// Extension OID name or identifier string.
+	pub oid: String,
+	pub critical: bool,
+	/// DER encoded extension value, without the OCTET STRING wrapper.
+	pub value: Buffer,
+}
+
+/// PKCS attribute, which unlike the AttributeTypeAndValue of a DN may have
+/// multiple values.
+#[napi(object, js_name = "ASN1Attribute")]
+pub struct ASN1Attribute {
+	pub r#type: ASN1OID,
+	#[napi(ts_type = "any[]")]
+	pub values: Array,
+}
+
+/// PolicyInformation of a certificate policies extension.
+#[napi(object, js_name = "ASN1PolicyInformation")]
+pub struct ASN1PolicyInformation {
+	/// Policy OID, which is "anyPolicy" for any policy.
+	pub policy: ASN1OID,
+	#[napi(ts_type = "any[]")]
+	pub qualifiers: Array,
+}
+
+/// DistributionPoint of a CRL distribution points extension.
+#[napi(object, js_name = "ASN1DistributionPoint")]
+pub struct ASN1DistributionPoint {
+	/// URIs of the full name of the distribution point.
+	pub urls: Vec<String>,
+}
+
+/// AccessDescription of an authority information access extension.
+#[napi(object, js_name = "ASN1AccessDescription")]
+pub struct ASN1AccessDescription {
+	/// Access method OID, such as "ocsp" or "caIssuers".
+	pub method: ASN1OID,
+	/// URI of the access location.
+	pub location: String,
+}
+
+/// GeneralName, such as of a subject alternative name extension. The
+/// otherName, x400Address and ediPartyName alternatives are only decoded,
+/// with their encoding as the value.
+#[napi(object, js_name = "ASN1GeneralName")]
+pub struct ASN1GeneralName {
+	#[napi(
+		ts_type = "'otherName' | 'rfc822Name' | 'dNSName' | 'x400Address' | 'directoryName' | 'ediPartyName' | 'uniformResourceIdentifier' | 'iPAddress' | 'registeredID'"
+	)]
+	pub r#type: String,
+	/// String of the name, or the DER encoded Name of a directoryName and the
+	/// address octets of an iPAddress.
+	pub value: Either<String, Buffer>,
+	/// Unicode form of the domain of an rfc822Name or dNSName with Punycode
+	/// labels, when requested while decoding and the labels are valid.
+	pub unicode: Option<String>,
+}
+
+/// Options for decoding GeneralNames.
+#[napi(object, js_name = "ASN1GeneralNamesOptions")]
+pub struct ASN1GeneralNamesOptions {
+	/// Decode Punycode labels of rfc822Name and dNSName domains for display,
+	/// keeping the value as encoded.
+	pub decode_punycode: Option<bool>,
+}
+
+/// Decoded fields of an X.509 certificate.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub(crate) struct Certificate {
+	pub version: u32,
+	pub serial: BigInt,
+	pub algorithm: ASN1OID,
+	pub issuer: ASN1Data,
+	pub not_before: DateTime<Utc>,
+	pub not_after: DateTime<Utc>,
+	pub subject: ASN1Data,
+	pub public_key: Vec<u8>,
+	pub issuer_unique_id: Option<Vec<u8>>,
+	pub subject_unique_id: Option<Vec<u8>>,
+	pub extensions: Option<ASN1Decoder>,
+	pub signature: Vec<u8>,
+}
+
+impl Certificate {
+	/// Encode the certificate as DER.
+	pub(crate) fn encode(&self) -> Result<Vec<u8>> {
+		if ASN1Decoder::new(self.public_key.clone()).get_tag() != &Tag::SEQUENCE {
+			bail!(ASN1NAPIError::InvalidCertificate);
+		}
+
+		let algorithm = ASN1Data::Array(vec![ASN1Data::Object(ASN1Object::Oid(
+			self.algorithm.clone(),
+		))]);
+
+		let mut tbs = vec![
+			ASN1Data::Object(ASN1Object::Context(ASN1Context::new(
+				0,
+				ASN1Data::Integer(i64::from(self.version) - 1),
+				"explicit",
+			))),
+			ASN1Data::BigInt(self.serial.clone()),
+			algorithm.clone(),
+			self.issuer.clone(),
+			ASN1Data::Array(vec![
+				ASN1Data::new_time(self.not_before),
+				ASN1Data::new_time(self.not_after),
+			]),
+			self.subject.clone(),
+			ASN1Data::Unknown(Any::new(self.public_key.clone())),
+		];
+
+		for (value, unique_id) in [(1, &self.issuer_unique_id), (2, &self.subject_unique_id)] {
+			if let Some(unique_id) = unique_id {
+				tbs.push(ASN1Data::Object(ASN1Object::Context(ASN1Context::new(
+					value,
+					ASN1Data::Object(ASN1Object::BitString(ASN1RawBitString::new(
+						BitString::from_vec(unique_id.clone()),
+						None,
+					))),
+					"implicit",
+				))));
+			}
+		}
+
+		if let Some(extensions) = &self.extensions {
+			tbs.push(ASN1Data::Object(ASN1Object::Context(ASN1Context::new(
+				3,
+				ASN1Data::Unknown(Any::new(extensions.get_raw().to_vec())),
+				"explicit",
+			))));
+		}
+
+		writer::encode(&ASN1Data::Array(vec![
+			ASN1Data::Array(tbs),
+			algorithm,
+			ASN1Data::Object(ASN1Object::BitString(ASN1RawBitString::new(
+				BitString::from_vec(self.signature.clone()),
+				None,
+			))),
+		]))
+	}
+
+	/// Convert into the JS certificate object.
+	pub(crate) fn into_js(self, env: Env) -> Result<ASN1Certificate> {
+		Ok(ASN1Certificate {
+			version: self.version,
+			serial: get_js_big_int_from_big_int(env, self.serial)?,
+			algorithm: self.algorithm.oid,
+			issuer: get_js_unknown_from_asn1_data(env, self.issuer)?,
+			not_before: self.not_before,
+			not_after: self.not_after,
+			subject: get_js_unknown_from_asn1_data(env, self.subject)?,
+			public_key: self.public_key.into(),
+			issuer_unique_id: self.issuer_unique_id.map(Buffer::from),
+			subject_unique_id: self.subject_unique_id.map(Buffer::from),
+			signature: self.signature.into(),
+		})
+	}
+}
+
+/// Task parsing a DER encoded certificate on the libuv thread pool.
+pub(crate) struct ParseCertificateTask(Vec<u8>);
+
+impl ParseCertificateTask {
+	pub(crate) fn new(data: Vec<u8>) -> Self {
+		Self(data)
+	}
+}
+
+impl Task for ParseCertificateTask {
+	type Output = Certificate;
+	type JsValue = ASN1Certificate;
+
+	fn compute(&mut self) -> napi::Result<Self::Output> {
+		Ok(Certificate::try_from(&ASN1Decoder::new(std::mem::take(
+			&mut self.0,
+		)))?)
+	}
+
+	fn resolve(&mut self, env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+		Ok(output.into_js(env)?)
+	}
+}
+
+impl ASN1Extension {
+	/// Encode the extension as DER, omitting the critical flag when it is
+	/// false as it is the DEFAULT.
+	pub(crate) fn encode(&self) -> Result<Vec<u8>> {
+		let mut extension = vec![ASN1Data::Object(ASN1Object::Oid(ASN1OID::new(&self.oid)))];
+
+		if self.critical {
+			extension.push(ASN1Data::Boolean(true));
+		}
+
+		extension.push(ASN1Data::Bytes(self.value.to_vec()));
+
+		writer::encode(&ASN1Data::Array(extension))
+	}
+}
+
+impl ASN1GeneralName {
+	/// Encode the GeneralName with the context tag of its alternative.
+	pub(crate) fn encode(&self) -> Result<Vec<u8>> {
+		match (self.r#type.as_str(), &self.value) {
+			("rfc822Name", Either::A(value)) => get_ia5_general_name(0x81, value),
+			("dNSName", Either::A(value)) => get_ia5_general_name(0x82, value),
+			("uniformResourceIdentifier", Either::A(value)) => get_ia5_general_name(0x86, value),
+			// directoryName is [4] EXPLICIT as Name is a CHOICE
+			("directoryName", Either::B(value)) => {
+				get_rdns_from_asn1(value)?;
+				writer::encode_tlv(0xA4, value)
+			}
+			("iPAddress", Either::A(value)) => match value.parse::<IpAddr>() {
+				Ok(IpAddr::V4(address)) => writer::encode_tlv(0x87, &address.octets()),
+				Ok(IpAddr::V6(address)) => writer::encode_tlv(0x87, &address.octets()),
+				Err(_) => bail!(ASN1NAPIError::InvalidDataEncoding),
+			},
+			// An address with a mask, as used by name constraints, is also allowed
+			("iPAddress", Either::B(value)) if matches!(value.len(), 4 | 8 | 16 | 32) => {
+				writer::encode_tlv(0x87, value)
+			}
+			("registeredID", Either::A(value)) => {
+				let mut encoded = writer::encode(&ASN1Data::Object(ASN1Object::Oid(
+					ASN1OID::try_from(value.as_str())?,
+				)))?;
+
+				// Replace the OBJECT IDENTIFIER tag with [8] IMPLICIT
+				encoded[0] = 0x88;
+				Ok(encoded)
+			}
+			_ => bail!(ASN1NAPIError::UnknownFieldProperty),
+		}
+	}
+}
+
+impl TryFrom<&ASN1Decoder> for ASN1GeneralName {
+	type Error = Error;
+
+	fn try_from(value: &ASN1Decoder) -> Result<Self, Self::Error> {
+		let tag = *value.get_tag();
+
+		if tag.class != Class::Context {
+			bail!(ASN1NAPIError::InvalidExtension);
+		}
+
+		let (r#type, name) = match (tag.value, value.get_is_constructed()) {
+			(0, true) => ("otherName", Either::B(value.get_raw().to_vec().into())),
+			(1, false) => ("rfc822Name", Either::A(get_ia5_contents(value)?)),
+			(2, false) => ("dNSName", Either::A(get_ia5_contents(value)?)),
+			(3, true) => ("x400Address", Either::B(value.get_raw().to_vec().into())),
+			(4, true) => (
+				"directoryName",
+				Either::B(value.get_contents()?.to_vec().into()),
+			),
+			(5, true) => ("ediPartyName", Either::B(value.get_raw().to_vec().into())),
+			(6, false) => (
+				"uniformResourceIdentifier",
+				Either::A(get_ia5_contents(value)?),
+			),
+			(7, false) => (
+				"iPAddress",
+				Either::B(value.get_contents()?.to_vec().into()),
+			),
+			// registeredID is [8] IMPLICIT OBJECT IDENTIFIER
+			(8, false) => {
+				let oid = ASN1Decoder::new_with_options(
+					writer::encode_tlv(0x06, value.get_contents()?)?,
+					value.get_options().clone(),
+				);
+
+				("registeredID", Either::A(oid.into_oid()?.oid))
+			}
+			_ => bail!(ASN1NAPIError::InvalidExtension),
+		};
+
+		Ok(Self {
+			r#type: r#type.to_string(),
+			value: name,
+			unicode: None,
+		})
+	}
+}
+
+impl TryFrom<&ASN1Decoder> for ASN1Extension {
+	type Error = Error;
+
+	fn try_from(value: &ASN1Decoder) -> Result<Self, Self::Error> {
+		let (oid, critical, extension) = get_extension_fields(value)?;
+
+		Ok(Self {
+			oid: oid.oid,
+			critical,
+			value: extension.into(),
+		})
+	}
+}
+
+impl TryFrom<&ASN1Decoder> for Certificate {
+	type Error = Error;
+
+	fn try_from(value: &ASN1Decoder) -> Result<Self, Self::Error> {
+		let (tbs, signature) = match value.get_element_decoders()?.as_slice() {
+			[tbs, _, signature] => (tbs.get_element_decoders()?, signature.clone()),
+			_ => bail!(ASN1NAPIError::InvalidCertificate),
+		};
+
+		let mut fields = tbs.iter().peekable();
+
+		// The version is optional and defaults to v1
+		let version = match fields.peek() {
+			Some(field) if *field.get_tag() == Tag::new(Class::Context, 0) => {
+				let version = field.get_at("contains")?.into_integer()?;
+				fields.next();
+				match version.checked_add(1).and_then(|v| u32::try_from(v).ok()) {
+					Some(version) => version,
+					None => bail!(ASN1NAPIError::InvalidCertificate),
+				}
+			}
+			_ => 1,
+		};
+
+		let (serial, algorithm, issuer, validity, subject, public_key) = match (
+			fields.next(),
+			fields.next(),
+			fields.next(),
+			fields.next(),
+			fields.next(),
+			fields.next(),
+		) {
+			(Some(a), Some(b), Some(c), Some(d), Some(e), Some(f)) => (a, b, c, d, e, f),
+			_ => bail!(ASN1NAPIError::InvalidCertificate),
+		};
+
+		// The unique identifiers and extensions are optional and in order
+		let issuer_unique_id = get_unique_id(&mut fields, 1)?;
+		let subject_unique_id = get_unique_id(&mut fields, 2)?;
+
+		let extensions = match fields.next() {
+			Some(field) if *field.get_tag() == Tag::new(Class::Context, 3) => {
+				Some(field.get_at("contains")?)
+			}
+			None => None,
+			_ => bail!(ASN1NAPIError::InvalidCertificate),
+		};
+
+		if fields.next().is_some() {
+			bail!(ASN1NAPIError::InvalidCertificate);
+		}
+
+		Ok(Self {
+			version,
+			serial: serial.clone().into_big_integer()?,
+			algorithm: algorithm.get_at("0")?.into_oid()?,
+			issuer: ASN1Data::try_from(issuer.clone())?,
+			not_before: validity.get_at("0")?.into_date()?,
+			not_after: validity.get_at("1")?.into_date()?,
+			subject: ASN1Data::try_from(subject.clone())?,
+			public_key: public_key.get_raw().to_vec(),
+			issuer_unique_id,
+			subject_unique_id,
+			extensions,
+			signature: signature.get_raw_bit_string()?.value.into_vec(),
+		})
+	}
+}
+
+impl TryFrom<ASN1TestCertificateOptions> for Certificate {
+	type Error = Error;
+
+	fn try_from(value: ASN1TestCertificateOptions) -> Result<Self, Self::Error> {
+		Ok(Self {
+			version: 3,
+			serial: get_big_int_from_js(value.serial.into_unknown()?)?,
+			algorithm: ASN1OID::new(value.algorithm),
+			issuer: ASN1Data::try_from(value.issuer)?,
+			not_before: value.not_before,
+			not_after: value.not_after,
+			subject: ASN1Data::try_from(value.subject)?,
+			public_key: value.public_key.to_vec(),
+			issuer_unique_id: None,
+			subject_unique_id: None,
+			extensions: None,
+			signature: vec![],
+		})
+	}
+}
+
+/// Get the certificates of a SEQUENCE OF Certificate or of concatenated
+/// certificates.
+pub(crate) fn get_certificate_chain(value: &ASN1Decoder) -> Result<Vec<Certificate>> {
+	if *value.get_tag() == Tag::SEQUENCE {
+		let certificates = value
+			.get_element_decoders()?
+			.iter()
+			.map(Certificate::try_from)
+			.collect::<Result<Vec<Certificate>>>();
+
+		// The elements of a single certificate are not certificates
+		if let Ok(certificates) = certificates {
+			return Ok(certificates);
+		}
+	}
+
+	split_elements(value.get_raw())
+		.map_err(|_| ASN1NAPIError::InvalidCertificate)?
+		.into_iter()
+		.map(|certificate| {
+			Certificate::try_from(&ASN1Decoder::new_with_options(
+				certificate.to_vec(),
+				value.get_options().clone(),
+			))
+		})
+		.collect()
+}
+
+/// Get the attribute of a SEQUENCE { type OID, values SET OF ANY }.
+pub(crate) fn get_attribute(env: Env, value: &ASN1Decoder) -> Result<ASN1Attribute> {
+	match value.get_element_decoders()?.as_slice() {
+		[oid, values] if *value.get_tag() == Tag::SEQUENCE && *values.get_tag() == Tag::SET => {
+			Ok(ASN1Attribute {
+				r#type: oid.into_oid()?,
+				values: get_js_array_from_asn_iter(env, values.clone().into_set_iter()?)?,
+			})
+		}
+		_ => bail!(ASN1NAPIError::InvalidAttribute),
+	}
+}
+
+/// Get the policies of a certificate policies extension value, which is a
+/// SEQUENCE OF PolicyInformation.
+pub(crate) fn get_certificate_policies(
+	env: Env,
+	value: &ASN1Decoder,
+) -> Result<Vec<ASN1PolicyInformation>> {
+	if *value.get_tag() != Tag::SEQUENCE {
+		bail!(ASN1NAPIError::InvalidExtension);
+	}
+
+	value
+		.get_element_decoders()?
+		.iter()
+		.map(|policy| {
+			if *policy.get_tag() != Tag::SEQUENCE {
+				bail!(ASN1NAPIError::InvalidExtension);
+			}
+
+			// PolicyInformation is SEQUENCE { policyIdentifier, policyQualifiers OPTIONAL }
+			let (oid, qualifiers) = match policy.get_element_decoders()?.as_slice() {
+				[oid] => (oid.into_oid()?, env.create_array(0)?),
+				[oid, qualifiers] if *qualifiers.get_tag() == Tag::SEQUENCE => {
+					(oid.into_oid()?, qualifiers.into_array(env)?)
+				}
+				_ => bail!(ASN1NAPIError::InvalidExtension),
+			};
+
+			Ok(ASN1PolicyInformation {
+				policy: oid,
+				qualifiers,
+			})
+		})
+		.collect()
+}
+
+/// Get the distribution points of a CRL distribution points extension value,
+/// which is a SEQUENCE OF DistributionPoint.
+pub(crate) fn get_crl_distribution_points(
+	value: &ASN1Decoder,
+) -> Result<Vec<ASN1DistributionPoint>> {
+	if *value.get_tag() != Tag::SEQUENCE {
+		bail!(ASN1NAPIError::InvalidExtension);
+	}
+
+	value
+		.get_element_decoders()?
+		.iter()
+		.map(|point| {
+			if *point.get_tag() != Tag::SEQUENCE {
+				bail!(ASN1NAPIError::InvalidExtension);
+			}
+
+			let mut urls = vec![];
+
+			// distributionPoint is [0] EXPLICIT DistributionPointName, as it is
+			// a CHOICE, of which fullName is [0] IMPLICIT GeneralNames
+			for field in point.get_element_decoders()? {
+				if *field.get_tag() != Tag::new(Class::Context, 0) {
+					continue;
+				}
+
+				let name = field.get_at("contains")?;
+
+				if *name.get_tag() != Tag::new(Class::Context, 0) || !name.get_is_constructed() {
+					continue;
+				}
+
+				for general_name in name.get_element_decoders()? {
+					if let Some(url) = get_uri(&general_name)? {
+						urls.push(url);
+					}
+				}
+			}
+
+			Ok(ASN1DistributionPoint { urls })
+		})
+		.collect()
+}
+
+/// Get the access descriptions with a URI location of an authority
+/// information access extension value, which is a SEQUENCE OF
+/// AccessDescription.
+pub(crate) fn get_authority_info_access(value: &ASN1Decoder) -> Result<Vec<ASN1AccessDescription>> {
+	if *value.get_tag() != Tag::SEQUENCE {
+		bail!(ASN1NAPIError::InvalidExtension);
+	}
+
+	let mut descriptions = vec![];
+
+	for description in value.get_element_decoders()? {
+		// AccessDescription is SEQUENCE { accessMethod, accessLocation }
+		match description.get_element_decoders()?.as_slice() {
+			[method, location] if *description.get_tag() == Tag::SEQUENCE => {
+				if let Some(location) = get_uri(location)? {
+					descriptions.push(ASN1AccessDescription {
+						method: method.into_oid()?,
+						location,
+					});
+				}
+			}
+			_ => bail!(ASN1NAPIError::InvalidExtension),
+		}
+	}
+
+	Ok(descriptions)
+}
+
+/// Get the URI of a GeneralName, which is a [6] IMPLICIT IA5String, or None
+/// for any other GeneralName.
+fn get_uri(general_name: &ASN1Decoder) -> Result<Option<String>> {
+	if *general_name.get_tag() != Tag::new(Class::Context, 6) || general_name.get_is_constructed() {
+		return Ok(None);
+	}
+
+	Ok(Some(get_ia5_contents(general_name)?))
+}
+
+/// Get the IA5String contents of an implicitly tagged GeneralName.
+fn get_ia5_contents(general_name: &ASN1Decoder) -> Result<String> {
+	match std::str::from_utf8(general_name.get_contents()?) {
+		Ok(value) if is_ia5_string(value) => Ok(value.to_string()),
+		_ => bail!(ASN1NAPIError::InvalidExtension),
+	}
+}
+
+/// Get the GeneralNames of decoded elements, with the Unicode form of their
+/// domains when Punycode decoding is requested. A domain which cannot be
+/// decoded is left without a Unicode form rather than failing every name.
+pub(crate) fn get_general_names(
+	names: Vec<ASN1Decoder>,
+	options: Option<ASN1GeneralNamesOptions>,
+) -> Result<Vec<ASN1GeneralName>> {
+	let decode_punycode = options.and_then(|options| options.decode_punycode) == Some(true);
+
+	names
+		.iter()
+		.map(|name| {
+			let mut name = ASN1GeneralName::try_from(name)?;
+
+			if let (true, Either::A(value)) = (decode_punycode, &name.value) {
+				name.unicode = match name.r#type.as_str() {
+					"dNSName" => get_unicode_domain(value),
+					"rfc822Name" => match value.rsplit_once('@') {
+						Some((local, domain)) => {
+							get_unicode_domain(domain).map(|domain| format!("{}@{}", local, domain))
+						}
+						None => get_unicode_domain(value),
+					},
+					_ => None,
+				};
+			}
+
+			Ok(name)
+		})
+		.collect()
+}
+
+/// Encode an IA5String GeneralName with an implicit context tag.
+fn get_ia5_general_name(tag: u32, value: &str) -> Result<Vec<u8>> {
+	if !is_ia5_string(value) {
+		bail!(ASN1NAPIError::InvalidStringEncoding);
+	}
+
+	writer::encode_tlv(tag, value.as_bytes())
+}
+
+/// Get the extensions of a certificate, of Extensions or of a single Extension.
+pub(crate) fn get_extensions(value: &ASN1Decoder) -> Result<Vec<ASN1Extension>> {
+	get_extension_decoders(value)?
+		.iter()
+		.map(ASN1Extension::try_from)
+		.collect()
+}
+
+/// Get decoders for the Extension sequences of a certificate, the
+/// Extensions of a certificate or a single Extension.
+fn get_extension_decoders(value: &ASN1Decoder) -> Result<Vec<ASN1Decoder>> {
+	let elements = value.get_element_decoders()?;
+	let is_extension = |element: &ASN1Decoder| {
+		*element.get_tag() == Tag::SEQUENCE
+			&& element
+				.get_at("0")
+				.map_or(false, |oid| *oid.get_tag() == Tag::OBJECT_IDENTIFIER)
+	};
+
+	if is_extension(value) {
+		return Ok(vec![value.clone()]);
+	} else if elements.iter().all(is_extension) {
+		return Ok(elements);
+	}
+
+	// Certificate, with the extensions in [3] EXPLICIT of the TBSCertificate
+	if let Some(tbs) = elements.first() {
+		for field in tbs.get_element_decoders()? {
+			if *field.get_tag() == Tag::new(Class::Context, 3) {
+				return get_extension_decoders(&field.get_at("contains")?);
+			}
+		}
+
+		return Ok(vec![]);
+	}
+
+	bail!(ASN1NAPIError::InvalidExtension)
+}
+
+/// Get the OID, critical flag and DER encoded value of an Extension.
+fn get_extension_fields(value: &ASN1Decoder) -> Result<(ASN1OID, bool, Vec<u8>)> {
+	match value.get_element_decoders()?.as_slice() {
+		[oid, extension] => Ok((oid.into_oid()?, false, extension.into_bytes()?)),
+		[oid, critical, extension] if *critical.get_tag() == Tag::BOOL => Ok((
+			oid.into_oid()?,
+			critical.into_bool()?,
+			extension.into_bytes()?,
+		)),
+		_ => bail!(ASN1NAPIError::InvalidExtension),
+	}
+}
+
+/// Get a decoder for the value of the extension with an OID name or
+/// identifier string, if present.
+fn get_extension_value<T: AsRef<str>>(value: &ASN1Decoder, oid: T) -> Result<Option<ASN1Decoder>> {
+	match get_entry_value_by_oid(&get_extension_decoders(value)?, &get_oid_elements(oid)?)? {
+		Some(extension) => Ok(Some(ASN1Decoder::new_with_options(
+			extension.into_bytes()?,
+			value.get_options().clone(),
+		))),
+		None => Ok(None),
+	}
+}
+
+/// Check if the keyIdentifier of the authority key identifier extension of
+/// a certificate is the subject key identifier of an issuer. A certificate
+/// without a keyIdentifier does not match any issuer.
+pub(crate) fn get_authority_key_id_matches(value: &ASN1Decoder, issuer_ski: &[u8]) -> Result<bool> {
+	let aki = match get_extension_value(value, "authorityKeyIdentifier")? {
+		Some(aki) if *aki.get_tag() == Tag::SEQUENCE => aki,
+		Some(_) => bail!(ASN1NAPIError::InvalidExtension),
+		None => return Ok(false),
+	};
+
+	// keyIdentifier [0] IMPLICIT OCTET STRING
+	for field in aki.get_element_decoders()? {
+		if *field.get_tag() == Tag::new(Class::Context, 0) && !field.get_is_constructed() {
+			return Ok(field.get_contents()? == issuer_ski);
+		}
+	}
+
+	Ok(false)
+}
+
+/// Get the bits of an optional [n] IMPLICIT BIT STRING unique identifier,
+/// advancing past it when present.
+fn get_unique_id<'a, I: Iterator<Item = &'a ASN1Decoder>>(
+	fields: &mut Peekable<I>,
+	value: u32,
+) -> Result<Option<Vec<u8>>> {
+	match fields.peek() {
+		Some(field) if *field.get_tag() == Tag::new(Class::Context, value) => {
+			// The contents start with the number of unused bits
+			let unique_id = match field.get_contents()?.split_first() {
+				Some((unused_bits, bits)) if !field.get_is_constructed() && *unused_bits < 8 => {
+					bits.to_vec()
+				}
+				_ => bail!(ASN1NAPIError::InvalidCertificate),
+			};
+
+			fields.next();
+			Ok(Some(unique_id))
+		}
+		_ => Ok(None),
+	}
+}
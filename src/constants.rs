@@ -12,11 +12,39 @@ pub(crate) const ASN1_OBJECT_NAME_KEY: &str = "name";
 pub(crate) const ASN1_OBJECT_FIELD_NAMES_KEY: &str = "fieldNames";
 /// Key string for "contains" attribute of struct objects.
 pub(crate) const ASN1_OBJECT_CONTAINS_KEY: &str = "contains";
+/// Key string for "bytes" attribute of integer objects.
+pub(crate) const ASN1_OBJECT_BYTES_KEY: &str = "bytes";
+/// Key string for "offset" attribute of date objects.
+pub(crate) const ASN1_OBJECT_OFFSET_KEY: &str = "offset";
 /// ASN1 Date format for GeneralizedTime but without milliseconds.
 pub(crate) const ASN1_DATE_TIME_GENERAL_FORMAT: &str = "%Y%m%d%H%M%SZ";
+/// GeneralizedTime used by RFC 5280 for a certificate which has no
+/// well-defined expiration date.
+pub(crate) const ASN1_DATE_TIME_NO_EXPIRY: &str = "99991231235959Z";
 /// ASN1 Date format for GeneralizedTime with milliseconds.
 pub(crate) const ASN1_DATE_TIME_GENERAL_FORMAT_WITH_MS: &str = "%Y%m%d%H%M%S%.3fZ";
+/// ASN1 Date format for parsing GeneralizedTime with any fraction of a
+/// second.
+pub(crate) const ASN1_DATE_TIME_GENERAL_FORMAT_WITH_FRACTION: &str = "%Y%m%d%H%M%S%.fZ";
 /// ASN1 Date format for UTCTime.
 pub(crate) const ASN1_DATE_TIME_UTC_FORMAT: &str = "%y%m%d%H%M%SZ";
+/// ASN1 Date format for GeneralizedTime without seconds.
+pub(crate) const ASN1_DATE_TIME_GENERAL_FORMAT_NO_SECONDS: &str = "%Y%m%d%H%MZ";
+/// ASN1 Date format for UTCTime without seconds.
+pub(crate) const ASN1_DATE_TIME_UTC_FORMAT_NO_SECONDS: &str = "%y%m%d%H%MZ";
+/// ASN1 Date format for GeneralizedTime with a time zone offset.
+pub(crate) const ASN1_DATE_TIME_GENERAL_FORMAT_OFFSET: &str = "%Y%m%d%H%M%S%z";
+/// ASN1 Date format for GeneralizedTime with milliseconds and a time zone
+/// offset.
+pub(crate) const ASN1_DATE_TIME_GENERAL_FORMAT_WITH_MS_OFFSET: &str = "%Y%m%d%H%M%S%.3f%z";
+/// ASN1 Date format for parsing GeneralizedTime with any fraction of a
+/// second and a time zone offset.
+pub(crate) const ASN1_DATE_TIME_GENERAL_FORMAT_WITH_FRACTION_OFFSET: &str = "%Y%m%d%H%M%S%.f%z";
+/// ASN1 Date format for UTCTime with a time zone offset.
+pub(crate) const ASN1_DATE_TIME_UTC_FORMAT_OFFSET: &str = "%y%m%d%H%M%S%z";
+/// First year which can be represented by a UTCTime.
+pub(crate) const ASN1_UTC_TIME_FIRST_YEAR: i32 = 1950;
+/// First year after the years which can be represented by a UTCTime.
+pub(crate) const ASN1_UTC_TIME_CUTOFF_YEAR: i32 = 2050;
 /// ASN1 null data.
 pub(crate) const ASN1_NULL: &[u8] = &[0x05, 0x00];
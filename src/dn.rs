@@ -0,0 +1,233 @@
+use anyhow::{bail, Result};
+use rasn::types::{Any, Implicit, ObjectIdentifier, Tag};
+
+use crate::{
+	asn1::ASN1Decoder,
+	objects::{ASN1Object, ASN1OID},
+	types::ASN1Data,
+	writer::encode,
+	ASN1NAPIError,
+};
+
+/// RFC 4514 short names for attribute types.
+static DN_ATTRIBUTE_NAMES: phf::Map<&'static str, &'static str> = phf_map! {
+	"2.5.4.3" => "CN",
+	"2.5.4.6" => "C",
+	"2.5.4.7" => "L",
+	"2.5.4.8" => "ST",
+	"2.5.4.9" => "STREET",
+	"2.5.4.10" => "O",
+	"2.5.4.11" => "OU",
+	"0.9.2342.19200300.100.1.1" => "UID",
+	"0.9.2342.19200300.100.1.25" => "DC",
+};
+
+/// An attribute type and value of a relative distinguished name.
+pub(crate) struct DnAttribute {
+	pub oid: Vec<u32>,
+	pub value: ASN1Decoder,
+}
+
+/// A relative distinguished name, which may have multiple attributes.
+pub(crate) type Rdn = Vec<DnAttribute>;
+
+/// Get the relative distinguished names of a DER encoded Name.
+pub(crate) fn get_rdns_from_asn1(data: &[u8]) -> Result<Vec<Rdn>> {
+	let name = ASN1Decoder::new(data.to_vec());
+
+	if *name.get_tag() != Tag::SEQUENCE {
+		bail!(ASN1NAPIError::InvalidDistinguishedName);
+	}
+
+	name.get_element_decoders()?
+		.into_iter()
+		.map(|rdn| {
+			if *rdn.get_tag() != Tag::SET {
+				bail!(ASN1NAPIError::InvalidDistinguishedName);
+			}
+
+			rdn.get_element_decoders()?
+				.into_iter()
+				.map(
+					|attribute| match attribute.get_element_decoders()?.as_slice() {
+						[oid, value] if *attribute.get_tag() == Tag::SEQUENCE => Ok(DnAttribute {
+							oid: oid.decode::<ObjectIdentifier>()?.to_vec(),
+							value: value.clone(),
+						}),
+						_ => bail!(ASN1NAPIError::InvalidDistinguishedName),
+					},
+				)
+				.collect()
+		})
+		.collect()
+}
+
+/// Get a canonical RFC 4514 string of a DER encoded Name, suitable for
+/// comparing and storing names. Values are normalized as per RFC 4518 and
+/// the attributes of multi-valued RDNs are sorted.
+pub(crate) fn get_canonical_dn(data: &[u8]) -> Result<String> {
+	let rdns = get_rdns_from_asn1(data)?
+		.iter()
+		.map(|rdn| {
+			let mut attributes = rdn
+				.iter()
+				.map(get_canonical_attribute)
+				.collect::<Result<Vec<String>>>()?;
+
+			attributes.sort();
+			Ok(attributes.join("+"))
+		})
+		.collect::<Result<Vec<String>>>()?;
+
+	// RFC 4514 renders the last RDN first
+	Ok(rdns.into_iter().rev().collect::<Vec<String>>().join(","))
+}
+
+/// Get a canonical DER encoding of a DER encoded Name, where string values
+/// are normalized as per RFC 4518 and encoded as UTF8Strings. Attributes of
+/// multi-valued RDNs are ordered by the SET encoding.
+fn get_canonical_dn_der(data: &[u8]) -> Result<Vec<u8>> {
+	let rdns = get_rdns_from_asn1(data)?
+		.iter()
+		.map(|rdn| ASN1Data::Set(rdn.iter().map(get_canonical_attribute_data).collect()))
+		.collect();
+
+	encode(&ASN1Data::Array(rdns))
+}
+
+/// Check if two DER encoded Names match after canonicalization.
+pub(crate) fn get_dn_matches(issuer: &[u8], subject: &[u8]) -> Result<bool> {
+	Ok(get_canonical_dn_der(issuer)? == get_canonical_dn_der(subject)?)
+}
+
+/// Get the canonical AttributeTypeAndValue of an attribute.
+fn get_canonical_attribute_data(attribute: &DnAttribute) -> ASN1Data {
+	let oid = attribute
+		.oid
+		.iter()
+		.map(u32::to_string)
+		.collect::<Vec<String>>()
+		.join(".");
+
+	let value = match attribute.value.into_string() {
+		Ok(value) => ASN1Data::Utf8String(Implicit::new(get_normalized_value(&value))),
+		Err(_) => ASN1Data::Unknown(Any::new(attribute.value.get_raw().to_vec())),
+	};
+
+	ASN1Data::Array(vec![
+		ASN1Data::Object(ASN1Object::Oid(ASN1OID::new(oid))),
+		value,
+	])
+}
+
+/// Get the canonical "type=value" string of an attribute.
+fn get_canonical_attribute(attribute: &DnAttribute) -> Result<String> {
+	let oid = attribute
+		.oid
+		.iter()
+		.map(u32::to_string)
+		.collect::<Vec<String>>()
+		.join(".");
+	let name = DN_ATTRIBUTE_NAMES
+		.get(oid.as_str())
+		.map_or(oid.as_str(), |name| *name);
+
+	let value = match attribute.value.into_string() {
+		Ok(value) => get_escaped_value(&get_normalized_value(&value)),
+		Err(_) => format!("#{}", hex::encode(attribute.value.get_raw())),
+	};
+
+	Ok(format!("{}={}", name, value))
+}
+
+/// Normalize a string value as per RFC 4518, folding the case and removing
+/// insignificant spaces.
+fn get_normalized_value(value: &str) -> String {
+	value
+		.split(char::is_whitespace)
+		.filter(|word| !word.is_empty())
+		.collect::<Vec<&str>>()
+		.join(" ")
+		.to_lowercase()
+}
+
+/// Escape a string value as per RFC 4514.
+fn get_escaped_value(value: &str) -> String {
+	let mut escaped = String::with_capacity(value.len());
+	let last = value.chars().count().saturating_sub(1);
+
+	for (index, c) in value.chars().enumerate() {
+		match c {
+			'"' | '+' | ',' | ';' | '<' | '>' | '\\' => {
+				escaped.push('\\');
+				escaped.push(c);
+			}
+			'#' | ' ' if index == 0 => {
+				escaped.push('\\');
+				escaped.push(c);
+			}
+			' ' if index == last => escaped.push_str("\\ "),
+			'\0' => escaped.push_str("\\00"),
+			_ => escaped.push(c),
+		}
+	}
+
+	escaped
+}
+
+#[cfg(test)]
+mod test {
+	use super::{get_canonical_dn, get_dn_matches, get_escaped_value, get_normalized_value};
+
+	#[test]
+	fn test_get_normalized_value() {
+		assert_eq!(get_normalized_value("  Keeta   Network "), "keeta network");
+		assert_eq!(get_normalized_value("CAFÉ"), "café");
+	}
+
+	#[test]
+	fn test_get_escaped_value() {
+		assert_eq!(get_escaped_value("a,b+c"), "a\\,b\\+c");
+		assert_eq!(get_escaped_value("#a "), "\\#a\\ ");
+		assert_eq!(get_escaped_value("a#b"), "a#b");
+	}
+
+	#[test]
+	fn test_get_canonical_dn() {
+		// C=US, O=Keeta, CN=Test as PrintableStrings
+		let name = hex::decode(
+			"302c310b3009060355040613025553310e300c060355040a13054b65657461\
+			 310d300b0603550403130454657374",
+		)
+		.expect("hex");
+
+		assert_eq!(get_canonical_dn(&name).unwrap(), "CN=test,O=keeta,C=us");
+		assert!(get_canonical_dn(&[0x02, 0x01, 0x01]).is_err());
+	}
+
+	#[test]
+	fn test_get_dn_matches() {
+		// C=US, O=Keeta, CN=Test as PrintableStrings
+		let name = hex::decode(
+			"302c310b3009060355040613025553310e300c060355040a13054b65657461\
+			 310d300b0603550403130454657374",
+		)
+		.expect("hex");
+		// C=US, O=KEETA, CN=Test as UTF8Strings
+		let other = hex::decode(
+			"302c310b300906035504060c025553310e300c060355040a0c054b45455441\
+			 310d300b06035504030c0454657374",
+		)
+		.expect("hex");
+		// C=US, O=Keeta, CN=Other
+		let different = hex::decode(
+			"302d310b3009060355040613025553310e300c060355040a13054b65657461\
+			 310e300c060355040313054f74686572",
+		)
+		.expect("hex");
+
+		assert!(get_dn_matches(&name, &other).unwrap());
+		assert!(!get_dn_matches(&name, &different).unwrap());
+		assert!(get_dn_matches(&name, &[0x02, 0x01, 0x01]).is_err());
+	}
+}
@@ -5,11 +5,17 @@ extern crate napi_derive;
 extern crate phf;
 
 mod asn1;
+mod certificate;
 mod constants;
+mod dn;
 mod macros;
 mod objects;
+mod options;
+mod pem;
+mod schema;
 mod types;
 mod utils;
+mod writer;
 
 use std::str::FromStr;
 
@@ -24,20 +30,29 @@ use constants::{
 };
 use napi::{
 	bindgen_prelude::{Array, Buffer},
-	Env, JsBigInt, JsBoolean, JsBuffer, JsDate, JsNumber, JsObject, JsString, JsUnknown, ValueType,
+	Env, JsBigInt, JsBoolean, JsBuffer, JsDate, JsFunction, JsNumber, JsObject, JsString,
+	JsUnknown, ValueType,
 };
 use num_bigint::BigInt;
 use thiserror::Error;
 
+use certificate::{
+	ASN1Certificate, ASN1Extension, ASN1GeneralName, ASN1TestCertificateOptions, Certificate,
+	ParseCertificateTask,
+};
+use dn::{get_canonical_dn, get_dn_matches};
 use objects::{
-	ASN1BitString, ASN1Context, ASN1ContextTag, ASN1Date, ASN1Object, ASN1Set, ASN1String,
-	TypedObject, ASN1OID,
+	get_oid_elements, get_signature_algorithm_family, ASN1BitString, ASN1Context, ASN1ContextTag,
+	ASN1Date, ASN1Object, ASN1Set, ASN1String, TypedObject, ASN1OID,
 };
+use options::ASN1DecodeOptions;
+use pem::{get_der_block_from_pem, get_der_blocks_from_pem, get_pem_from_der};
 use types::{ASN1Data, JsValue};
 use utils::{
 	convert_string_kind_to_tag, get_big_int_from_js, get_string_kind_tag, get_vec_from_js_unknown,
 	get_words_from_big_int,
 };
+use writer::ASN1RawElement;
 
 /// Library errors
 #[derive(Error, Eq, PartialEq, Debug)]
@@ -64,12 +79,42 @@ enum ASN1NAPIError {
 	InvalidStringEncoding,
 	#[error("Invalid UTC time")]
 	InvalidUtcTime,
+	#[error("Invalid generalized time")]
+	InvalidGeneralizedTime,
+	#[error("The provided date encoding policy is not valid")]
+	InvalidDateEncodingPolicy,
 	#[error("Can only handle Universal simple types for this operation")]
 	InvalidSimpleTypesOnly,
 	#[error("Context data must be a sequence")]
 	InvalidContextNonSequence,
 	#[error("Could not encode provided data into ASN.1 format")]
 	InvalidDataEncoding,
+	#[error("The provided path does not address an element")]
+	InvalidPath,
+	#[error("The provided data is not a distinguished name")]
+	InvalidDistinguishedName,
+	#[error("The provided data is not a certificate")]
+	InvalidCertificate,
+	#[error("The provided data is not a certificate extension")]
+	InvalidExtension,
+	#[error("The provided data is not an attribute")]
+	InvalidAttribute,
+	#[error("The provided tag is not a valid identifier")]
+	InvalidTag,
+	#[error("The value length exceeds the maximum value length")]
+	InvalidValueLength,
+	#[error("The provided data does not match the schema")]
+	InvalidSchema,
+	#[error("The provided head count exceeds the number of elements")]
+	InvalidHeadCount,
+	#[error("The provided integer is negative")]
+	InvalidNegativeInteger,
+	#[error("The provided OID mode is not supported")]
+	InvalidOidMode,
+	#[error("The provided digest algorithm is not supported")]
+	InvalidDigestAlgorithm,
+	#[error("The provided data or label is not valid PEM")]
+	InvalidPem,
 }
 
 /// Helper to convert a JS bigint to a JS Buffer
@@ -102,6 +147,142 @@ pub fn get_big_int_from_string(env: Env, data: String) -> Result<JsBigInt> {
 	get_js_big_int_from_big_int(env, BigInt::from_str(&data)?)
 }
 
+/// Check if an OID is equal to or under the arc of another OID, comparing
+/// each arc rather than the string. Names are resolved to their OIDs.
+#[napi(strict, js_name = "oidStartsWith")]
+pub fn oid_starts_with(oid: String, prefix: String) -> Result<bool> {
+	Ok(get_oid_elements(oid)?.starts_with(&get_oid_elements(prefix)?))
+}
+
+/// Get the OID name or identifier string of an OID object.
+#[napi(strict, js_name = "oidToString")]
+pub fn oid_to_string(#[napi(ts_arg_type = "ASN1OID")] oid: JsObject) -> Result<String> {
+	Ok(ASN1OID::try_from(oid)?.oid)
+}
+
+/// Get an OID object from an OID name or identifier string.
+#[napi(strict, js_name = "stringToOid")]
+pub fn string_to_oid(oid: String) -> Result<ASN1OID> {
+	ASN1OID::try_from(oid)
+}
+
+/// Get the family of a signature algorithm OID name or identifier string,
+/// for choosing the verification routine.
+#[napi(
+	strict,
+	js_name = "signatureAlgorithmFamily",
+	ts_return_type = "'ecdsa' | 'rsa' | 'eddsa' | 'unknown'"
+)]
+pub fn signature_algorithm_family(oid: String) -> Result<&'static str> {
+	get_signature_algorithm_family(oid)
+}
+
+/// Parse the fields of a DER encoded X.509 certificate.
+#[napi(strict, js_name = "parseCertificate")]
+pub fn parse_certificate(env: Env, data: Buffer) -> Result<ASN1Certificate> {
+	Certificate::try_from(&ASN1Decoder::new(data.to_vec()))?.into_js(env)
+}
+
+/// Parse many DER encoded X.509 certificates, each on the thread pool, so the
+/// event loop is not blocked. The promise is rejected if any fails to parse.
+#[napi(
+	strict,
+	js_name = "parseCertificatesAsync",
+	ts_return_type = "Promise<ASN1Certificate[]>"
+)]
+pub fn parse_certificates_async(env: Env, buffers: Vec<Buffer>) -> Result<JsObject> {
+	let mut promises = env.create_array_with_length(buffers.len())?;
+
+	for (index, data) in buffers.iter().enumerate() {
+		let task = env.spawn(ParseCertificateTask::new(data.to_vec()))?;
+		promises.set_element(index as u32, task.promise_object())?;
+	}
+
+	let promise = env
+		.get_global()?
+		.get_named_property::<JsUnknown>("Promise")?
+		.coerce_to_object()?;
+	let all = promise.get_named_property::<JsFunction>("all")?;
+
+	Ok(all.call(Some(&promise), &[promises])?.coerce_to_object()?)
+}
+
+/// Encode a certificate extension from an already encoded extension value.
+#[napi(strict, js_name = "encodeExtension")]
+pub fn encode_extension(oid: String, critical: bool, value: Buffer) -> Result<Buffer> {
+	Ok(ASN1Extension {
+		oid,
+		critical,
+		value,
+	}
+	.encode()?
+	.into())
+}
+
+/// Encode DER data as PEM with a label, such as "CERTIFICATE".
+#[napi(strict, js_name = "derToPem")]
+pub fn der_to_pem(der: Buffer, label: String) -> Result<String> {
+	get_pem_from_der(&der, &label)
+}
+
+/// Decode the DER data of the first block of PEM encoded data.
+#[napi(strict, js_name = "pemToDer")]
+pub fn pem_to_der(pem: String) -> Result<Buffer> {
+	Ok(get_der_block_from_pem(&pem)?.into())
+}
+
+/// Decode the DER data of every block of PEM encoded data, such as of a CA
+/// bundle with several certificates.
+#[napi(strict, js_name = "pemToDerAll")]
+pub fn pem_to_der_all(pem: String) -> Result<Vec<Buffer>> {
+	Ok(get_der_blocks_from_pem(&pem)?
+		.into_iter()
+		.map(Buffer::from)
+		.collect())
+}
+
+/// Encode a GeneralName, such as for building a subject alternative name
+/// extension.
+#[napi(strict, js_name = "encodeGeneralName")]
+pub fn encode_general_name(input: ASN1GeneralName) -> Result<Buffer> {
+	Ok(input.encode()?.into())
+}
+
+/// Encode a TLV from a single identifier octet and already encoded contents.
+#[napi(strict, js_name = "encodeTlv")]
+pub fn encode_tlv(tag: u32, content: Buffer) -> Result<Buffer> {
+	Ok(writer::encode_tlv(tag, &content)?.into())
+}
+
+/// Encode an element with explicit tag classes and numbers, without any tag
+/// inference, for protocols which do not follow the usual conventions.
+#[napi(strict, js_name = "encodeRaw")]
+pub fn encode_raw(element: ASN1RawElement) -> Result<Buffer> {
+	Ok(writer::encode_raw(&element)?.into())
+}
+
+/// Build a DER encoded certificate for testing. The certificate is not
+/// signed and has an empty signature.
+#[napi(strict, js_name = "buildTestCertificate")]
+pub fn build_test_certificate(options: ASN1TestCertificateOptions) -> Result<Buffer> {
+	Ok(Certificate::try_from(options)?.encode()?.into())
+}
+
+/// Get a canonical string of a DER encoded distinguished name, suitable as
+/// a key for comparing and storing names.
+#[napi(strict, js_name = "canonicalizeDn")]
+pub fn canonicalize_dn(data: Buffer) -> Result<String> {
+	get_canonical_dn(&data)
+}
+
+/// Check if two DER encoded distinguished names match, such as the issuer of
+/// a certificate and the subject of its issuer, by comparing their canonical
+/// DER encodings.
+#[napi(strict, js_name = "dnMatches")]
+pub fn dn_matches(issuer_der: Buffer, subject_der: Buffer) -> Result<bool> {
+	get_dn_matches(&issuer_der, &subject_der)
+}
+
 /// Convert JS input into ASN1 BER encoded data.
 // May return undefined if "allowUndefined" is set to true and the input is undefined.
 #[napi(strict, js_name = "JStoASN1", ts_return_type = "any")]
@@ -118,7 +299,7 @@ pub fn js_to_asn1(
 		}
 	}
 
-	let instance = ASN1Encoder::js_new(data);
+	let instance = ASN1Encoder::js_new(data, None);
 
 	match instance {
 		Ok(encoder) => Ok(encoder
@@ -137,6 +318,7 @@ pub fn js_to_asn1(
 pub fn asn1_to_js(
 	env: Env,
 	#[napi(ts_arg_type = "ArrayBuffer")] data: JsUnknown,
+	options: Option<ASN1DecodeOptions>,
 ) -> Result<JsUnknown> {
 	let asn1 = match data.get_type()? {
 		ValueType::String => {
@@ -146,7 +328,10 @@ pub fn asn1_to_js(
 		_ => ASN1Decoder::new(get_vec_from_js_unknown(data)?),
 	};
 
-	get_js_unknown_from_asn1_data(env, ASN1Data::try_from(asn1)?)
+	get_js_unknown_from_asn1_data(
+		env,
+		ASN1Data::try_from(asn1.with_options(options.unwrap_or_default()))?,
+	)
 }
 
 /// Get a JsObject from an iterator of ASN1Data.
@@ -198,15 +383,16 @@ pub(crate) fn get_js_context_tag_from_asn1_context(
 	env: Env,
 	data: ASN1Context,
 ) -> Result<ASN1ContextTag> {
-	Ok(ASN1ContextTag::new(
-		data.value,
-		get_js_unknown_from_asn1_data(env, *data.contains)?,
-		data.kind,
-	))
+	let contains = match *data.contains {
+		ASN1Data::Undefined => None,
+		contains => Some(get_js_unknown_from_asn1_data(env, contains)?),
+	};
+
+	Ok(ASN1ContextTag::new(data.value, contains, data.kind))
 }
 
 /// Get a JsUnknown from ASN1Data.
-fn get_js_unknown_from_asn1_data(env: Env, data: ASN1Data) -> Result<JsUnknown> {
+pub(crate) fn get_js_unknown_from_asn1_data(env: Env, data: ASN1Data) -> Result<JsUnknown> {
 	JsUnknown::try_from(JsValue::try_from((env, data))?)
 }
 
@@ -220,6 +406,21 @@ fn get_js_obj_from_asn_string(env: Env, value: String, kind: String) -> Result<J
 	Ok(obj)
 }
 
+/// Get a JsObject from an ASN1OID, with the name when the OID mode
+/// includes it.
+fn get_js_obj_from_asn_oid(env: Env, data: &ASN1OID) -> Result<JsObject> {
+	let mut obj = env.create_object()?;
+
+	obj.set_named_property::<JsString>(ASN1_OBJECT_TYPE_KEY, env.create_string(ASN1OID::TYPE)?)?;
+	obj.set_named_property::<JsString>(ASN1OID::TYPE, env.create_string(&data.oid)?)?;
+
+	if let Some(name) = &data.name {
+		obj.set_named_property::<JsString>(ASN1_OBJECT_NAME_KEY, env.create_string(name)?)?;
+	}
+
+	Ok(obj)
+}
+
 /// Get a JsObject from an ANS1Object.
 /// Note: Wrapping native objects results in empty JS objects and therefore
 /// must be manually built.
@@ -227,21 +428,9 @@ fn get_js_obj_from_asn_object(env: Env, data: ASN1Object) -> Result<JsObject> {
 	let mut obj = env.create_object()?;
 
 	match data {
-		ASN1Object::Oid(val) => {
-			obj.set_named_property::<JsString>(
-				ASN1_OBJECT_TYPE_KEY,
-				env.create_string(ASN1OID::TYPE)?,
-			)?;
-			obj.set_named_property::<JsString>(ASN1OID::TYPE, env.create_string(&val.oid)?)?;
-		}
+		ASN1Object::Oid(val) => obj = get_js_obj_from_asn_oid(env, &val)?,
 		ASN1Object::Set(val) => {
-			let mut oid = env.create_object()?;
-
-			oid.set_named_property::<JsString>(
-				ASN1_OBJECT_TYPE_KEY,
-				env.create_string(ASN1OID::TYPE)?,
-			)?;
-			oid.set_named_property::<JsString>(ASN1OID::TYPE, env.create_string(&val.name.oid)?)?;
+			let oid = get_js_obj_from_asn_oid(env, &val.name)?;
 
 			obj.set_named_property::<JsString>(
 				ASN1_OBJECT_TYPE_KEY,
@@ -336,10 +525,14 @@ fn get_js_obj_from_asn_object(env: Env, data: ASN1Object) -> Result<JsObject> {
 				ASN1_OBJECT_VALUE_KEY,
 				env.create_uint32(val.value)?,
 			)?;
-			obj.set_named_property::<JsUnknown>(
-				"contains",
-				get_js_unknown_from_asn1_data(env, *val.contains)?,
-			)?;
+
+			/* An empty explicit context tag has no contents */
+			if *val.contains != ASN1Data::Undefined {
+				obj.set_named_property::<JsUnknown>(
+					"contains",
+					get_js_unknown_from_asn1_data(env, *val.contains)?,
+				)?;
+			}
 		}
 		ASN1Object::Struct(val) => {
 			obj.set_named_property::<JsString>(
@@ -1,7 +1,7 @@
 use std::collections::VecDeque;
 
 use anyhow::{bail, Error, Result};
-use chrono::{DateTime, Datelike, FixedOffset, Utc};
+use chrono::{DateTime, FixedOffset, Utc};
 use napi::bindgen_prelude::FromNapiValue;
 use napi::{Env, JsArrayBuffer, JsBuffer, JsNumber, JsObject, JsString, JsUnknown, ValueType};
 use rasn::{
@@ -13,12 +13,13 @@ use rasn::{
 
 use crate::{
 	constants::*,
+	options::get_decode_options,
 	type_object,
-	types::ASN1Data,
+	types::{ASN1Data, ASN1TimeKind},
 	utils::{
-		get_oid_elements_from_string, get_string_from_js, get_string_from_oid_elements,
-		get_string_kind_from_tag, get_string_kind_tag, header_length, is_ia5_string,
-		is_printable_string,
+		get_generalized_time_string, get_latin1_from_string, get_oid_elements_from_string,
+		get_string_from_js, get_string_from_oid_elements, get_string_kind_from_tag,
+		get_string_kind_tag, header_length, is_ia5_string, is_printable_string,
 	},
 	ASN1Decoder, ASN1NAPIError,
 };
@@ -39,6 +40,17 @@ static NAME_TO_OID_MAP: phf::Map<&'static str, &'static [u32]> = phf_map! {
 	"commonName" => &[2, 5, 4, 3],
 	"hash" => &[1, 3, 6, 1, 4, 1, 8301, 3, 2, 2, 1, 1],
 	"hashData" => &[2, 16, 840, 1, 101, 3, 3, 1, 3],
+	"subjectAltName" => &[2, 5, 29, 17],
+	"authorityKeyIdentifier" => &[2, 5, 29, 35],
+	"userPrincipalName" => &[1, 3, 6, 1, 4, 1, 311, 20, 2, 3],
+	"extensionRequest" => &[1, 2, 840, 113549, 1, 9, 14],
+	"signingTime" => &[1, 2, 840, 113549, 1, 9, 5],
+	"cRLDistributionPoints" => &[2, 5, 29, 31],
+	"certificatePolicies" => &[2, 5, 29, 32],
+	"anyPolicy" => &[2, 5, 29, 32, 0],
+	"authorityInfoAccess" => &[1, 3, 6, 1, 5, 5, 7, 1, 1],
+	"ocsp" => &[1, 3, 6, 1, 5, 5, 7, 48, 1],
+	"caIssuers" => &[1, 3, 6, 1, 5, 5, 7, 48, 2],
 };
 
 /// HashMap for an OID string to name
@@ -57,6 +69,44 @@ static OID_TO_NAME_MAP: phf::Map<&'static str, &'static str> = phf_map! {
 	"2.5.4.3" => "commonName",
 	"1.3.6.1.4.1.8301.3.2.2.1.1" => "hash",
 	"2.16.840.1.101.3.3.1.3" => "hashData",
+	"2.5.29.17" => "subjectAltName",
+	"2.5.29.35" => "authorityKeyIdentifier",
+	"1.3.6.1.4.1.311.20.2.3" => "userPrincipalName",
+	"1.2.840.113549.1.9.14" => "extensionRequest",
+	"1.2.840.113549.1.9.5" => "signingTime",
+	"2.5.29.31" => "cRLDistributionPoints",
+	"2.5.29.32" => "certificatePolicies",
+	"2.5.29.32.0" => "anyPolicy",
+	"1.3.6.1.5.5.7.1.1" => "authorityInfoAccess",
+	"1.3.6.1.5.5.7.48.1" => "ocsp",
+	"1.3.6.1.5.5.7.48.2" => "caIssuers",
+};
+
+/// HashMap for a signature algorithm OID string to its family
+static SIGNATURE_ALGORITHM_FAMILY_MAP: phf::Map<&'static str, &'static str> = phf_map! {
+	"1.2.840.10045.2.1" => "ecdsa",
+	"1.2.840.10045.4.1" => "ecdsa",
+	"1.2.840.10045.4.3.1" => "ecdsa",
+	"1.2.840.10045.4.3.2" => "ecdsa",
+	"1.2.840.10045.4.3.3" => "ecdsa",
+	"1.2.840.10045.4.3.4" => "ecdsa",
+	"2.16.840.1.101.3.4.3.9" => "ecdsa",
+	"2.16.840.1.101.3.4.3.10" => "ecdsa",
+	"2.16.840.1.101.3.4.3.11" => "ecdsa",
+	"2.16.840.1.101.3.4.3.12" => "ecdsa",
+	"1.2.840.113549.1.1.1" => "rsa",
+	"1.2.840.113549.1.1.5" => "rsa",
+	"1.2.840.113549.1.1.10" => "rsa",
+	"1.2.840.113549.1.1.11" => "rsa",
+	"1.2.840.113549.1.1.12" => "rsa",
+	"1.2.840.113549.1.1.13" => "rsa",
+	"1.2.840.113549.1.1.14" => "rsa",
+	"2.16.840.1.101.3.4.3.13" => "rsa",
+	"2.16.840.1.101.3.4.3.14" => "rsa",
+	"2.16.840.1.101.3.4.3.15" => "rsa",
+	"2.16.840.1.101.3.4.3.16" => "rsa",
+	"1.3.101.112" => "eddsa",
+	"1.3.101.113" => "eddsa",
 };
 
 /// Container for object types. Automatically decodes to specified type
@@ -106,6 +156,7 @@ pub struct ASN1OID {
 	#[napi(ts_type = "'oid'")]
 	pub r#type: &'static str,
 	pub oid: String,
+	pub name: Option<String>,
 }
 
 /// ASN1 Set.
@@ -128,7 +179,7 @@ pub struct ASN1String {
 	#[napi(ts_type = "'string'")]
 	pub r#type: &'static str,
 	pub value: String,
-	#[napi(ts_type = "'ia5' | 'utf8' | 'printable'")]
+	#[napi(ts_type = "'ia5' | 'utf8' | 'printable' | 'teletex'")]
 	pub kind: String,
 }
 
@@ -142,6 +193,9 @@ pub struct ASN1Date {
 	#[napi(ts_type = "'utc' | 'general' | 'default'")]
 	pub kind: Option<String>,
 	pub date: DateTime<FixedOffset>,
+	/// Time zone offset in minutes, for a GeneralizedTime encoded with an
+	/// offset rather than in UTC.
+	pub offset: Option<i32>,
 }
 
 /// ASN1 JS Context Tag.
@@ -152,8 +206,9 @@ pub struct ASN1ContextTag {
 	#[napi(ts_type = "'implicit' | 'explicit'")]
 	pub kind: String,
 	pub value: u32,
+	/// Contents of the context tag, absent for an empty explicit context tag.
 	#[napi(ts_type = "any")]
-	pub contains: JsUnknown,
+	pub contains: Option<JsUnknown>,
 }
 
 /// ASN1 JS bit string.
@@ -182,6 +237,39 @@ pub struct ASN1StructShim {
 	pub contains: JsUnknown,
 }
 
+/// Shim to surface explicit SEQUENCE wrappers in generated TypeScript
+/// declarations only.
+#[napi(object, js_name = "ASN1Sequence")]
+#[allow(dead_code)]
+pub struct ASN1SequenceShim {
+	#[napi(ts_type = "'sequence'")]
+	pub r#type: &'static str,
+	#[napi(ts_type = "ASN1AnyJS[]")]
+	pub value: JsUnknown,
+}
+
+/// Shim to surface integers given as their exact content octets in generated
+/// TypeScript declarations only.
+#[napi(object, js_name = "ASN1IntegerBytes")]
+#[allow(dead_code)]
+pub struct ASN1IntegerShim {
+	#[napi(ts_type = "'integer'")]
+	pub r#type: &'static str,
+	#[napi(ts_type = "Buffer | number[]")]
+	pub bytes: JsUnknown,
+}
+
+/// Shim to surface explicit SET wrappers in generated TypeScript
+/// declarations only.
+#[napi(object, js_name = "ASN1SetOf")]
+#[allow(dead_code)]
+pub struct ASN1SetOfShim {
+	#[napi(ts_type = "'set'")]
+	pub r#type: &'static str,
+	#[napi(ts_type = "ASN1AnyJS[]")]
+	pub value: JsUnknown,
+}
+
 /// Get an oid as u32 words from a canonically named identifier.
 fn get_oid_from_name<T: AsRef<str>>(name: T) -> Result<&'static [u32]> {
 	if let Some(oid) = NAME_TO_OID_MAP.get(name.as_ref()) {
@@ -191,6 +279,26 @@ fn get_oid_from_name<T: AsRef<str>>(name: T) -> Result<&'static [u32]> {
 	}
 }
 
+/// Get an oid as u32 words from a canonical name or an identifier string.
+pub(crate) fn get_oid_elements<T: AsRef<str>>(oid: T) -> Result<Vec<u32>> {
+	if oid.as_ref().contains('.') {
+		get_oid_elements_from_string(oid)
+	} else {
+		Ok(get_oid_from_name(oid)?.to_vec())
+	}
+}
+
+/// Get the family of a signature algorithm OID name or identifier string,
+/// which is "unknown" for OIDs which are not signature algorithms.
+pub(crate) fn get_signature_algorithm_family<T: AsRef<str>>(oid: T) -> Result<&'static str> {
+	let oid = get_string_from_oid_elements(get_oid_elements(oid)?)?;
+
+	Ok(SIGNATURE_ALGORITHM_FAMILY_MAP
+		.get(oid.as_str())
+		.copied()
+		.unwrap_or("unknown"))
+}
+
 /// Get an identifer string from an Oid.
 fn get_oid_string_from_oid(oid: &Oid) -> String {
 	oid.iter()
@@ -243,6 +351,27 @@ impl ASN1OID {
 		Self {
 			r#type: Self::TYPE,
 			oid: oid.as_ref().into(),
+			name: None,
+		}
+	}
+
+	/// Create a new instance of ASN1OID from words, represented as per the
+	/// OID mode of the decode options.
+	pub(crate) fn new_with_mode(value: &[u32], mode: Option<&str>) -> Result<Self> {
+		let oid = match Oid::new(value) {
+			Some(oid) => oid,
+			None => bail!(ASN1NAPIError::UnknownOid),
+		};
+
+		match mode {
+			None => Self::try_from(value),
+			Some("name") => Ok(Self::new(get_name_from_oid(oid)?)),
+			Some("dotted") => Ok(Self::new(get_string_from_oid_elements(value)?)),
+			Some("both") => Ok(Self {
+				name: get_name_from_oid(oid).ok().map(String::from),
+				..Self::new(get_string_from_oid_elements(value)?)
+			}),
+			Some(_) => bail!(ASN1NAPIError::InvalidOidMode),
 		}
 	}
 }
@@ -252,7 +381,7 @@ impl ASN1Set {
 	pub fn new(name: ASN1OID, value: ASN1String) -> Self {
 		Self {
 			r#type: Self::TYPE,
-			value: value,
+			value,
 			name,
 		}
 	}
@@ -290,6 +419,7 @@ impl ASN1String {
 			"ia5" => Tag::IA5_STRING,
 			"utf8" => Tag::UTF8_STRING,
 			"printable" => Tag::PRINTABLE_STRING,
+			"teletex" => Tag::TELETEX_STRING,
 			_ => Tag::UTF8_STRING,
 		}
 	}
@@ -297,7 +427,7 @@ impl ASN1String {
 
 impl ASN1ContextTag {
 	/// Create a new instance of an ASN1ContextTag from a number and JsUnknown.
-	pub fn new(value: u32, contains: JsUnknown, kind: String) -> Self {
+	pub fn new(value: u32, contains: Option<JsUnknown>, kind: String) -> Self {
 		Self {
 			r#type: Self::TYPE,
 			kind,
@@ -326,6 +456,9 @@ type_object!(ASN1String, "string");
 type_object!(ASN1Date, "date");
 type_object!(ASN1ContextTag, "context");
 type_object!(ASN1Struct, "struct");
+type_object!(ASN1SequenceShim, "sequence");
+type_object!(ASN1IntegerShim, "integer");
+type_object!(ASN1SetOfShim, "set");
 
 impl Encode for ASN1RawBitString {
 	fn encode_with_tag<E: Encoder>(&self, encoder: &mut E, tag: Tag) -> Result<(), E::Error> {
@@ -353,15 +486,8 @@ impl Decode for ASN1RawBitString {
 
 impl Encode for ASN1OID {
 	fn encode_with_tag<E: Encoder>(&self, encoder: &mut E, tag: Tag) -> Result<(), E::Error> {
-		if self.oid.contains(['.']) {
-			if let Ok(result) = get_oid_elements_from_string(&self.oid) {
-				encoder.encode_object_identifier(tag, &result)?;
-				Ok(())
-			} else {
-				Err(<E as Encoder>::Error::custom(ASN1NAPIError::UnknownOid))
-			}
-		} else if let Ok(result) = get_oid_from_name(&self.oid) {
-			encoder.encode_object_identifier(tag, result)?;
+		if let Ok(result) = get_oid_elements(&self.oid) {
+			encoder.encode_object_identifier(tag, &result)?;
 			Ok(())
 		} else {
 			Err(<E as Encoder>::Error::custom(ASN1NAPIError::UnknownOid))
@@ -371,12 +497,11 @@ impl Encode for ASN1OID {
 
 impl Decode for ASN1OID {
 	fn decode_with_tag<D: Decoder>(decoder: &mut D, tag: Tag) -> Result<Self, D::Error> {
-		if let Ok(result) = ASN1OID::try_from(decoder.decode_object_identifier(tag)?.to_vec()) {
-			Ok(result)
-		} else {
-			Err(<D as rasn::Decoder>::Error::custom(
-				ASN1NAPIError::UnknownOid,
-			))
+		let oid = decoder.decode_object_identifier(tag)?;
+
+		match ASN1OID::new_with_mode(&oid, get_decode_options().oid_mode.as_deref()) {
+			Ok(result) => Ok(result),
+			Err(error) => Err(<D as rasn::Decoder>::Error::custom(error)),
 		}
 	}
 }
@@ -405,11 +530,13 @@ impl Decode for ASN1Set {
 				let name = ObjectIdentifier::decode(decoder)?;
 				let value = Any::decode(decoder)?;
 
-				if let Ok(oid) = ASN1OID::try_from(name.to_vec()) {
+				if let Ok(oid) =
+					ASN1OID::new_with_mode(&name, get_decode_options().oid_mode.as_deref())
+				{
 					let asn1 = ASN1Decoder::new(value.as_bytes().to_owned());
 
 					if let Ok(value) = asn1.into_string() {
-						let kind = get_string_kind_from_tag(*asn1.get_tag());
+						let kind = asn1.get_string_kind();
 						let value = ASN1String::new(value, Some(kind.to_string()));
 						Ok(Self::new(oid, value))
 					} else {
@@ -437,6 +564,15 @@ impl Encode for ASN1String {
 			"printable" => {
 				encoder.encode_utf8_string(Tag::PRINTABLE_STRING, &self.value)?;
 			}
+			"teletex" => {
+				if let Some(data) = get_latin1_from_string(&self.value) {
+					encoder.encode_octet_string(Tag::TELETEX_STRING, &data)?;
+				} else {
+					return Err(<E as Encoder>::Error::custom(
+						ASN1NAPIError::InvalidStringEncoding,
+					));
+				}
+			}
 			_ => {
 				return Err(<E as Encoder>::Error::custom(
 					ASN1NAPIError::UnknownStringFormat,
@@ -459,54 +595,10 @@ impl Decode for ASN1String {
 impl Encode for ASN1Date {
 	fn encode_with_tag<E: Encoder>(&self, encoder: &mut E, _: Tag) -> Result<(), E::Error> {
 		if let Some(kind) = self.kind.as_deref() {
-			match kind {
-				"utc" => {
-					encoder.encode_utf8_string(
-						Tag::UTC_TIME,
-						&self
-							.date
-							.with_timezone(&Utc)
-							.format(ASN1_DATE_TIME_UTC_FORMAT)
-							.to_string(),
-					)?;
-				}
-				"general" => {
-					let format = if &self.date.timestamp_millis() % 1000 == 0 {
-						ASN1_DATE_TIME_GENERAL_FORMAT
-					} else {
-						ASN1_DATE_TIME_GENERAL_FORMAT_WITH_MS
-					};
+			let kind = ASN1TimeKind::from_name(kind)
+				.unwrap_or_else(|| ASN1TimeKind::from_date(&self.date));
 
-					encoder.encode_utf8_string(
-						Tag::GENERALIZED_TIME,
-						&self.date.with_timezone(&Utc).format(format).to_string(),
-					)?;
-				}
-				_ => {
-					if self.date.year() < 2050 {
-						encoder.encode_utf8_string(
-							Tag::UTC_TIME,
-							&self
-								.date
-								.with_timezone(&Utc)
-								.format(ASN1_DATE_TIME_UTC_FORMAT)
-								.to_string(),
-						)?;
-					} else {
-						let format = if &self.date.timestamp_millis() % 1000 == 0 {
-							ASN1_DATE_TIME_GENERAL_FORMAT
-						} else {
-							ASN1_DATE_TIME_GENERAL_FORMAT_WITH_MS
-						};
-
-						encoder.encode_utf8_string(
-							Tag::GENERALIZED_TIME,
-							&self.date.with_timezone(&Utc).format(format).to_string(),
-						)?;
-					}
-				}
-			}
-			Ok(())
+			encode_time(encoder, &self.date, kind)
 		} else {
 			Err(<E as Encoder>::Error::custom(
 				ASN1NAPIError::UnknownDateFormat,
@@ -515,6 +607,34 @@ impl Encode for ASN1Date {
 	}
 }
 
+/// Encode a date as the given kind of time.
+fn encode_time<E: Encoder>(
+	encoder: &mut E,
+	date: &DateTime<FixedOffset>,
+	kind: ASN1TimeKind,
+) -> Result<(), E::Error> {
+	/* UTCTime can only represent the years 1950 through 2049 and
+	 * GeneralizedTime the years through 9999 */
+	if !kind.is_valid_for(date) {
+		return Err(<E as Encoder>::Error::custom(match kind {
+			ASN1TimeKind::Utc => ASN1NAPIError::InvalidUtcTime,
+			ASN1TimeKind::General => ASN1NAPIError::InvalidGeneralizedTime,
+		}));
+	}
+
+	let time = match kind {
+		ASN1TimeKind::Utc => date
+			.with_timezone(&Utc)
+			.format(ASN1_DATE_TIME_UTC_FORMAT)
+			.to_string(),
+		ASN1TimeKind::General => get_generalized_time_string(date),
+	};
+
+	encoder.encode_utf8_string(kind.get_tag(), &time)?;
+
+	Ok(())
+}
+
 // @TODO Date
 impl Decode for ASN1Date {
 	fn decode_with_tag<D: Decoder>(_: &mut D, _: Tag) -> Result<Self, D::Error> {
@@ -535,7 +655,7 @@ impl Encode for ASN1Context {
 			if let Ok(data) = rasn::der::encode(&*self.contains) {
 				if let Ok(skip_bytes) = header_length(&data) {
 					/* Modify the tag to be the new context tag */
-					encoder.encode_octet_string(tag, &data[skip_bytes..].to_vec().as_slice())?;
+					encoder.encode_octet_string(tag, data[skip_bytes..].to_vec().as_slice())?;
 				} else {
 					return Err(<E as Encoder>::Error::custom(ASN1NAPIError::UknownContext));
 				}
@@ -557,6 +677,12 @@ impl Decode for ASN1Context {
 			let bytes = asn1.get_raw();
 			if let Ok(header_len) = header_length(bytes) {
 				let inner_bytes = bytes[header_len..].to_vec();
+
+				// An empty explicit context tag is only a presence flag
+				if inner_bytes.is_empty() {
+					return Ok(Self::new(tag.value, ASN1Data::Undefined, "explicit"));
+				}
+
 				// Use ASN1Decoder to reuse the crate's tag and JS-type inference before decoding
 				if let Ok(data) = ASN1Data::try_from(ASN1Decoder::new(inner_bytes)) {
 					return Ok(Self::new(tag.value, data, "explicit"));
@@ -564,7 +690,7 @@ impl Decode for ASN1Context {
 			}
 		} else {
 			let bytes = asn1.get_raw();
-			let length = header_length(bytes).map_err(|e| <D as Decoder>::Error::custom(e))?;
+			let length = header_length(bytes).map_err(<D as Decoder>::Error::custom)?;
 			let extracted_data = bytes[length..].to_vec();
 			let data = ASN1Data::Unknown(Any::new(extracted_data));
 			return Ok(Self::new(tag.value, data, "implicit"));
@@ -578,6 +704,28 @@ impl Encode for ASN1Data {
 	fn encode_with_tag<E: Encoder>(&self, encoder: &mut E, _: Tag) -> Result<(), E::Error> {
 		match self {
 			ASN1Data::Array(arr) => arr.encode(encoder),
+			/* The rasn SET encoder orders fields by tag, which drops repeated tags */
+			ASN1Data::Set(values) => {
+				/* DER orders the elements of a SET OF by their encodings */
+				let mut encoded = values
+					.iter()
+					.map(rasn::ber::encode)
+					.collect::<Result<Vec<Vec<u8>>, _>>()
+					.map_err(|_| {
+						<E as Encoder>::Error::custom(ASN1NAPIError::InvalidDataEncoding)
+					})?;
+				encoded.sort();
+
+				encoder.encode_sequence(Tag::SET, |encoder| {
+					for value in encoded {
+						Any::new(value).encode(encoder)?;
+					}
+
+					Ok(())
+				})?;
+
+				Ok(())
+			}
 			ASN1Data::Unknown(any) => any.encode(encoder),
 			ASN1Data::Object(obj) => match obj {
 				ASN1Object::Oid(oid) => oid.encode(encoder),
@@ -589,19 +737,7 @@ impl Encode for ASN1Data {
 				ASN1Object::Struct(struct_value) => struct_value.encode(encoder),
 			},
 			ASN1Data::Utf8String(string) => string.encode_with_tag(encoder, Tag::UTF8_STRING),
-			ASN1Data::UtcTime(date) => date.encode(encoder),
-			ASN1Data::GeneralizedTime(date) => {
-				let format = if &date.timestamp_millis() % 1000 == 0 {
-					ASN1_DATE_TIME_GENERAL_FORMAT
-				} else {
-					ASN1_DATE_TIME_GENERAL_FORMAT_WITH_MS
-				};
-
-				date.naive_utc()
-					.format(format)
-					.to_string()
-					.encode_with_tag(encoder, Tag::GENERALIZED_TIME)
-			}
+			ASN1Data::Time { instant, kind } => encode_time(encoder, instant, *kind),
 			ASN1Data::Undefined => Ok(()),
 			_ => {
 				if let Ok(open) = Open::try_from(self) {
@@ -847,16 +983,17 @@ impl TryFrom<JsObject> for ASN1String {
 				let kind = get_string_from_js(kind)?;
 				let value = get_string_from_js(value)?;
 
-				if kind == "printable" && !is_printable_string(&value) {
-					bail!(ASN1NAPIError::InvalidStringEncoding)
-				} else if kind == "ia5" && !is_ia5_string(&value) {
+				if (kind == "printable" && !is_printable_string(&value))
+					|| (kind == "ia5" && !is_ia5_string(&value))
+					|| (kind == "teletex" && get_latin1_from_string(&value).is_none())
+				{
 					bail!(ASN1NAPIError::InvalidStringEncoding)
 				}
 
 				Ok(Self {
 					r#type: Self::TYPE,
-					kind: kind,
-					value: value,
+					kind,
+					value,
 				})
 			} else {
 				bail!(ASN1NAPIError::UnknownStringFormat)
@@ -881,9 +1018,22 @@ impl TryFrom<JsObject> for ASN1Date {
 		};
 
 		if date.is_date()? {
-			let date = DateTime::<FixedOffset>::from_unknown(date)?;
+			let mut date = DateTime::<FixedOffset>::from_unknown(date)?;
+			let offset = match obj.get_named_property::<JsUnknown>(ASN1_OBJECT_OFFSET_KEY)? {
+				offset if offset.get_type()? == ValueType::Number => {
+					Some(offset.coerce_to_number()?.get_int32()?)
+				}
+				_ => None,
+			};
 
-			if kind.as_deref() == Some("utc") && date.year() >= 2050 {
+			if let Some(offset) = offset {
+				match offset.checked_mul(60).and_then(FixedOffset::east_opt) {
+					Some(offset) => date = date.with_timezone(&offset),
+					None => bail!(ASN1NAPIError::UnknownDateFormat),
+				}
+			}
+
+			if kind.as_deref() == Some("utc") && !ASN1TimeKind::Utc.is_valid_for(&date) {
 				bail!(ASN1NAPIError::InvalidUtcTime)
 			}
 
@@ -891,6 +1041,7 @@ impl TryFrom<JsObject> for ASN1Date {
 				r#type: Self::TYPE,
 				kind,
 				date,
+				offset,
 			})
 		} else {
 			bail!(ASN1NAPIError::UnknownDateFormat)
@@ -978,7 +1129,7 @@ impl TryFrom<JsObject> for ASN1Object {
 
 #[cfg(test)]
 mod test {
-	use super::ASN1OID;
+	use super::{get_signature_algorithm_family, ASN1OID};
 
 	#[test]
 	fn test_asn1oid_try_from_string() {
@@ -1005,4 +1156,51 @@ mod test {
 
 		assert_eq!(ASN1OID::try_from(input).unwrap(), result);
 	}
+
+	#[test]
+	fn test_asn1oid_new_with_mode() {
+		let known = [2, 5, 4, 5];
+		let unknown = [1, 2, 3, 4];
+
+		assert_eq!(
+			ASN1OID::new_with_mode(&known, None).unwrap(),
+			ASN1OID::new("serialNumber")
+		);
+		assert_eq!(
+			ASN1OID::new_with_mode(&known, Some("name")).unwrap(),
+			ASN1OID::new("serialNumber")
+		);
+		assert_eq!(
+			ASN1OID::new_with_mode(&known, Some("dotted")).unwrap(),
+			ASN1OID::new("2.5.4.5")
+		);
+		assert_eq!(
+			ASN1OID::new_with_mode(&known, Some("both"))
+				.unwrap()
+				.name
+				.as_deref(),
+			Some("serialNumber")
+		);
+		assert_eq!(
+			ASN1OID::new_with_mode(&unknown, Some("both")).unwrap(),
+			ASN1OID::new("1.2.3.4")
+		);
+		assert!(ASN1OID::new_with_mode(&unknown, Some("name")).is_err());
+		assert!(ASN1OID::new_with_mode(&known, Some("other")).is_err());
+	}
+
+	#[test]
+	fn test_get_signature_algorithm_family() {
+		assert_eq!(
+			get_signature_algorithm_family("sha256WithEcDSA").unwrap(),
+			"ecdsa"
+		);
+		assert_eq!(get_signature_algorithm_family("ed25519").unwrap(), "eddsa");
+		assert_eq!(
+			get_signature_algorithm_family("1.2.840.113549.1.1.11").unwrap(),
+			"rsa"
+		);
+		assert_eq!(get_signature_algorithm_family("sha256").unwrap(), "unknown");
+		assert!(get_signature_algorithm_family("notAnOid").is_err());
+	}
 }
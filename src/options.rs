@@ -0,0 +1,144 @@
+use std::cell::RefCell;
+
+use anyhow::{bail, Result};
+
+use crate::{
+	constants::{ASN1_UTC_TIME_CUTOFF_YEAR, ASN1_UTC_TIME_FIRST_YEAR},
+	ASN1NAPIError,
+};
+
+/// Options controlling how ASN.1 data is decoded into JS values.
+#[napi(object, js_name = "ASN1DecodeOptions")]
+#[derive(Hash, Clone, Default, Eq, PartialEq, Debug)]
+pub struct ASN1DecodeOptions {
+	/// How UTCTime and GeneralizedTime values are returned. Defaults to 'date'.
+	#[napi(ts_type = "'date' | 'iso' | 'asn1'")]
+	pub date_format: Option<String>,
+	/// Recover from common encoder mistakes, such as a constructed bit set
+	/// on a primitive only type.
+	pub lenient: Option<bool>,
+	/// Remove trailing NUL padding from decoded strings.
+	pub trim_nul: Option<bool>,
+	/// Decode TeletexString values as UTF8String, so they are re-encoded as
+	/// UTF8String rather than TeletexString.
+	pub teletex_as_utf8: Option<bool>,
+	/// Maximum declared length of OCTET STRING, BIT STRING and string
+	/// values, which are rejected before being read when longer.
+	pub max_value_length: Option<u32>,
+	/// Keep the time zone offset of GeneralizedTime values, so they are
+	/// re-encoded with the same offset rather than in UTC.
+	pub preserve_time_offset: Option<bool>,
+	/// How OIDs are returned. 'name' requires every OID to have a known
+	/// name, 'dotted' always uses the dotted form and 'both' returns the
+	/// dotted form with the name when it is known. Defaults to the name when
+	/// it is known and the dotted form otherwise.
+	#[napi(ts_type = "'name' | 'dotted' | 'both'")]
+	pub oid_mode: Option<String>,
+}
+
+/// Policy for how dates are encoded, choosing between UTCTime and
+/// GeneralizedTime and the fraction of a second and time zone offset of a
+/// GeneralizedTime. The kind of a date object other than 'default' is kept.
+#[napi(object, js_name = "ASN1DateEncodingPolicy")]
+#[derive(Hash, Clone, Default, Eq, PartialEq, Debug)]
+pub struct ASN1DateEncodingPolicy {
+	/// Year from which dates are encoded as GeneralizedTime rather than
+	/// UTCTime, from 1950 through 2050. Defaults to 2050.
+	pub cutoff_year: Option<i32>,
+	/// Encode every date as GeneralizedTime, even when UTCTime can
+	/// represent it.
+	pub always_general: Option<bool>,
+	/// Keep the time zone offset of a GeneralizedTime rather than encoding
+	/// it in UTC. Defaults to true.
+	pub preserve_offset: Option<bool>,
+	/// Maximum number of digits of the fraction of a second of a
+	/// GeneralizedTime, up to 9. Trailing zeros are left out, as is a zero
+	/// fraction. Defaults to milliseconds when they are not 0.
+	pub fractional_digits: Option<u32>,
+}
+
+impl ASN1DateEncodingPolicy {
+	/// Check that the cutoff year and fractional digits are in range.
+	pub(crate) fn validate(&self) -> Result<()> {
+		if !(ASN1_UTC_TIME_FIRST_YEAR..=ASN1_UTC_TIME_CUTOFF_YEAR)
+			.contains(&self.cutoff_year.unwrap_or(ASN1_UTC_TIME_CUTOFF_YEAR))
+			|| self.fractional_digits.unwrap_or(0) > 9
+		{
+			bail!(ASN1NAPIError::InvalidDateEncodingPolicy);
+		}
+
+		Ok(())
+	}
+}
+
+thread_local! {
+	/// Options in effect for decoders created while decoding nested data.
+	static DECODE_OPTIONS: RefCell<ASN1DecodeOptions> = RefCell::new(ASN1DecodeOptions::default());
+
+	/// Policy in effect for dates converted from JS or encoded.
+	static DATE_ENCODING_POLICY: RefCell<ASN1DateEncodingPolicy> =
+		RefCell::new(ASN1DateEncodingPolicy::default());
+}
+
+/// Get the decode options currently in effect.
+pub(crate) fn get_decode_options() -> ASN1DecodeOptions {
+	DECODE_OPTIONS.with(|options| options.borrow().clone())
+}
+
+/// Run a closure with the provided decode options in effect. The rasn
+/// Decode implementations cannot be passed any state, so nested decoders
+/// pick up the options from here.
+pub(crate) fn with_decode_options<T, F: FnOnce() -> T>(options: &ASN1DecodeOptions, f: F) -> T {
+	let previous = DECODE_OPTIONS.with(|current| current.replace(options.clone()));
+	let result = f();
+
+	DECODE_OPTIONS.with(|current| current.replace(previous));
+	result
+}
+
+/// Get the date encoding policy currently in effect.
+pub(crate) fn get_date_encoding_policy() -> ASN1DateEncodingPolicy {
+	DATE_ENCODING_POLICY.with(|policy| policy.borrow().clone())
+}
+
+/// Run a closure with the provided date encoding policy in effect, for the
+/// same reason as the decode options.
+pub(crate) fn with_date_encoding_policy<T, F: FnOnce() -> T>(
+	policy: &ASN1DateEncodingPolicy,
+	f: F,
+) -> T {
+	let previous = DATE_ENCODING_POLICY.with(|current| current.replace(policy.clone()));
+	let result = f();
+
+	DATE_ENCODING_POLICY.with(|current| current.replace(previous));
+	result
+}
+
+#[cfg(test)]
+mod test {
+	use super::ASN1DateEncodingPolicy;
+
+	#[test]
+	fn test_date_encoding_policy_validate() {
+		let policy = ASN1DateEncodingPolicy {
+			cutoff_year: Some(2000),
+			fractional_digits: Some(9),
+			..Default::default()
+		};
+
+		assert!(policy.validate().is_ok());
+		assert!(ASN1DateEncodingPolicy::default().validate().is_ok());
+		assert!(ASN1DateEncodingPolicy {
+			cutoff_year: Some(2051),
+			..Default::default()
+		}
+		.validate()
+		.is_err());
+		assert!(ASN1DateEncodingPolicy {
+			fractional_digits: Some(10),
+			..Default::default()
+		}
+		.validate()
+		.is_err());
+	}
+}
@@ -0,0 +1,157 @@
+use anyhow::{bail, Result};
+
+use crate::ASN1NAPIError;
+
+/// Number of base64 characters on each line of PEM encoded data.
+const PEM_LINE_LENGTH: usize = 64;
+
+/// Get the PEM encoding of DER data with a label, such as "CERTIFICATE", as
+/// per RFC 7468.
+pub(crate) fn get_pem_from_der(der: &[u8], label: &str) -> Result<String> {
+	if !is_pem_label(label) {
+		bail!(ASN1NAPIError::InvalidPem);
+	}
+
+	let encoded = base64::encode(der);
+	let mut pem = format!("-----BEGIN {}-----\n", label);
+
+	// Base64 is ASCII, so the lines may be sliced at any byte
+	for start in (0..encoded.len()).step_by(PEM_LINE_LENGTH) {
+		pem.push_str(&encoded[start..encoded.len().min(start + PEM_LINE_LENGTH)]);
+		pem.push('\n');
+	}
+
+	pem.push_str(&format!("-----END {}-----\n", label));
+
+	Ok(pem)
+}
+
+/// Get the DER data of the first block of PEM encoded data, without
+/// reading any later blocks. Text before the block is ignored.
+pub(crate) fn get_der_block_from_pem(pem: &str) -> Result<Vec<u8>> {
+	match get_next_der_block(&mut pem.lines().map(str::trim))? {
+		Some(der) => Ok(der),
+		None => bail!(ASN1NAPIError::InvalidPem),
+	}
+}
+
+/// Get the DER data of each block of PEM encoded data, in order. Text
+/// outside of the blocks, such as comments in a bundle, is ignored.
+pub(crate) fn get_der_blocks_from_pem(pem: &str) -> Result<Vec<Vec<u8>>> {
+	let mut blocks = Vec::new();
+	let mut lines = pem.lines().map(str::trim);
+
+	while let Some(der) = get_next_der_block(&mut lines)? {
+		blocks.push(der);
+	}
+
+	if blocks.is_empty() {
+		bail!(ASN1NAPIError::InvalidPem);
+	}
+
+	Ok(blocks)
+}
+
+/// Get the DER data of the next block of the trimmed lines of PEM encoded
+/// data, advancing past the end of the block.
+fn get_next_der_block<'a, I: Iterator<Item = &'a str>>(lines: &mut I) -> Result<Option<Vec<u8>>> {
+	while let Some(line) = lines.next() {
+		let label = match line
+			.strip_prefix("-----BEGIN ")
+			.and_then(|line| line.strip_suffix("-----"))
+		{
+			Some(label) if is_pem_label(label) => label,
+			Some(_) => bail!(ASN1NAPIError::InvalidPem),
+			None => continue,
+		};
+
+		let end = format!("-----END {}-----", label);
+		let mut encoded = String::new();
+
+		loop {
+			match lines.next() {
+				Some(line) if line == end => break,
+				Some(line) if !line.starts_with("-----") => {
+					encoded.extend(line.chars().filter(|c| !c.is_whitespace()))
+				}
+				_ => bail!(ASN1NAPIError::InvalidPem),
+			}
+		}
+
+		match base64::decode(&encoded) {
+			Ok(der) => return Ok(Some(der)),
+			Err(_) => bail!(ASN1NAPIError::InvalidPem),
+		}
+	}
+
+	Ok(None)
+}
+
+/// Check if a label is valid, being printable characters other than "-"
+/// with single spaces between words.
+fn is_pem_label(label: &str) -> bool {
+	!label.starts_with(' ')
+		&& !label.ends_with(' ')
+		&& !label.contains("  ")
+		&& label
+			.chars()
+			.all(|c| c == ' ' || (c.is_ascii_graphic() && c != '-'))
+}
+
+#[cfg(test)]
+mod test {
+	use super::{get_der_block_from_pem, get_der_blocks_from_pem, get_pem_from_der, is_pem_label};
+
+	#[test]
+	fn test_get_pem_from_der() {
+		let pem = get_pem_from_der(&[0x30, 0x03, 0x02, 0x01, 0x01], "CERTIFICATE").unwrap();
+
+		assert_eq!(
+			pem,
+			"-----BEGIN CERTIFICATE-----\nMAMCAQE=\n-----END CERTIFICATE-----\n"
+		);
+		assert_eq!(
+			get_pem_from_der(&[0; 60], "X")
+				.unwrap()
+				.lines()
+				.nth(1)
+				.map(str::len),
+			Some(64)
+		);
+		assert!(get_pem_from_der(&[0x30, 0x00], "BAD-LABEL").is_err());
+	}
+
+	#[test]
+	fn test_get_der_blocks_from_pem() {
+		let pem =
+			"comment\n-----BEGIN CERTIFICATE-----\r\nMAMC\r\nAQE=\r\n-----END CERTIFICATE-----\n\
+		           -----BEGIN PUBLIC KEY-----\nMAA=\n-----END PUBLIC KEY-----\n";
+
+		assert_eq!(
+			get_der_blocks_from_pem(pem).unwrap(),
+			vec![vec![0x30, 0x03, 0x02, 0x01, 0x01], vec![0x30, 0x00]]
+		);
+		assert!(get_der_blocks_from_pem("").is_err());
+		assert!(get_der_blocks_from_pem("-----BEGIN X-----\nMAA=\n").is_err());
+		assert!(get_der_blocks_from_pem("-----BEGIN X-----\nMAA=\n-----END Y-----\n").is_err());
+		assert!(get_der_blocks_from_pem("-----BEGIN X-----\nM!A=\n-----END X-----\n").is_err());
+	}
+
+	#[test]
+	fn test_get_der_block_from_pem() {
+		let pem = "-----BEGIN X-----\nMAA=\n-----END X-----\n-----BEGIN Y-----\nM!A=\n";
+
+		assert_eq!(get_der_block_from_pem(pem).unwrap(), vec![0x30, 0x00]);
+		assert!(get_der_blocks_from_pem(pem).is_err());
+		assert!(get_der_block_from_pem("comment\n").is_err());
+	}
+
+	#[test]
+	fn test_is_pem_label() {
+		assert!(is_pem_label("CERTIFICATE"));
+		assert!(is_pem_label("X509 CRL"));
+		assert!(is_pem_label(""));
+		assert!(!is_pem_label("X509  CRL"));
+		assert!(!is_pem_label("EC-KEY"));
+	}
+}
@@ -0,0 +1,219 @@
+use anyhow::{bail, Error, Result};
+use napi::{JsString, JsUnknown, ValueType};
+use num_bigint::BigInt;
+use rasn::types::{Class, ObjectIdentifier, Tag};
+
+use crate::{
+	asn1::ASN1Decoder,
+	utils::{get_boolean_from_js, get_string_from_js, is_string_tag},
+	ASN1NAPIError,
+};
+
+/// Kinds of elements which may be described by a schema.
+const SCHEMA_KINDS: &[&str] = &[
+	"integer",
+	"boolean",
+	"string",
+	"oid",
+	"buffer",
+	"date",
+	"bitstring",
+	"sequence",
+	"set",
+	"context",
+	"null",
+	"any",
+];
+
+/// Expected shape of ASN.1 data, such as `{ seq: ['integer', { oid: true },
+/// { seq: '*' }] }`. A kind may be given as a string or as an object with
+/// the kind as its only key, whose value is true, '*' for any elements or
+/// an array of the schemas of the elements.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub(crate) enum ASN1Schema {
+	Kind(&'static str),
+	Constructed(&'static str, Option<Vec<ASN1Schema>>),
+}
+
+impl ASN1Schema {
+	/// Check if decoded data conforms to the schema. Malformed data does not
+	/// conform to any schema other than "any".
+	pub(crate) fn matches(&self, value: &ASN1Decoder) -> bool {
+		match self {
+			Self::Kind(kind) => matches_kind(value, kind),
+			Self::Constructed(kind, None) => matches_kind(value, kind),
+			Self::Constructed(kind, Some(schemas)) => {
+				matches_kind(value, kind)
+					&& value.get_element_decoders().map_or(false, |elements| {
+						elements.len() == schemas.len()
+							&& elements
+								.iter()
+								.zip(schemas)
+								.all(|(element, schema)| schema.matches(element))
+					})
+			}
+		}
+	}
+}
+
+impl TryFrom<JsUnknown> for ASN1Schema {
+	type Error = Error;
+
+	fn try_from(value: JsUnknown) -> Result<Self, Self::Error> {
+		match value.get_type()? {
+			ValueType::String => Ok(Self::Kind(get_schema_kind(&get_string_from_js(value)?)?)),
+			ValueType::Object if !value.is_array()? => {
+				let object = value.coerce_to_object()?;
+				let keys = object.get_property_names()?;
+
+				if keys.get_array_length()? != 1 {
+					bail!(ASN1NAPIError::InvalidSchema);
+				}
+
+				let key = keys.get_element::<JsString>(0)?.into_utf8()?.into_owned()?;
+				let kind = get_schema_kind(&key)?;
+				let contents = object.get_named_property::<JsUnknown>(&key)?;
+
+				match contents.get_type()? {
+					ValueType::Boolean => match get_boolean_from_js(contents)? {
+						true => Ok(Self::Kind(kind)),
+						false => bail!(ASN1NAPIError::InvalidSchema),
+					},
+					ValueType::String if is_constructed_kind(kind) => {
+						match get_string_from_js(contents)?.as_str() {
+							"*" => Ok(Self::Constructed(kind, None)),
+							_ => bail!(ASN1NAPIError::InvalidSchema),
+						}
+					}
+					ValueType::Object if contents.is_array()? && is_constructed_kind(kind) => {
+						let contents = contents.coerce_to_object()?;
+						let mut schemas = Vec::new();
+
+						for index in 0..contents.get_array_length()? {
+							schemas
+								.push(Self::try_from(contents.get_element::<JsUnknown>(index)?)?);
+						}
+
+						Ok(Self::Constructed(kind, Some(schemas)))
+					}
+					_ => bail!(ASN1NAPIError::InvalidSchema),
+				}
+			}
+			_ => bail!(ASN1NAPIError::InvalidSchema),
+		}
+	}
+}
+
+/// Get the kind of a schema name, where "seq" is short for "sequence".
+pub(crate) fn get_schema_kind(name: &str) -> Result<&'static str> {
+	let name = if name == "seq" { "sequence" } else { name };
+
+	match SCHEMA_KINDS.iter().find(|kind| **kind == name) {
+		Some(kind) => Ok(kind),
+		None => bail!(ASN1NAPIError::InvalidSchema),
+	}
+}
+
+/// Whether a kind contains elements which may be described by a schema.
+fn is_constructed_kind(kind: &str) -> bool {
+	matches!(kind, "sequence" | "set" | "context")
+}
+
+/// Check if a tag is of a kind, without decoding the contents.
+pub(crate) fn is_kind_tag(kind: &str, tag: Tag) -> bool {
+	match (kind, tag) {
+		("integer", Tag::INTEGER)
+		| ("boolean", Tag::BOOL)
+		| ("oid", Tag::OBJECT_IDENTIFIER)
+		| ("buffer", Tag::OCTET_STRING)
+		| ("date", Tag::UTC_TIME | Tag::GENERALIZED_TIME)
+		| ("bitstring", Tag::BIT_STRING)
+		| ("sequence", Tag::SEQUENCE)
+		| ("set", Tag::SET)
+		| ("null", Tag::NULL)
+		| ("any", _) => true,
+		("string", tag) => is_string_tag(tag),
+		("context", tag) => tag.class == Class::Context,
+		_ => false,
+	}
+}
+
+/// Check if decoded data is of a kind and can be decoded as that kind.
+fn matches_kind(value: &ASN1Decoder, kind: &str) -> bool {
+	is_kind_tag(kind, *value.get_tag())
+		&& match kind {
+			"integer" => value.decode::<BigInt>().is_ok(),
+			"boolean" => value.into_bool().is_ok(),
+			"string" => value.into_string().is_ok(),
+			"oid" => value.decode::<ObjectIdentifier>().is_ok(),
+			"buffer" => value.into_bytes().is_ok(),
+			"date" => value.into_date().is_ok(),
+			"bitstring" => value.get_raw_bit_string().is_ok(),
+			"sequence" | "set" => value.get_element_decoders().is_ok(),
+			"context" => !value.get_is_constructed() || value.get_element_decoders().is_ok(),
+			"null" => value.into_null().is_ok(),
+			_ => true,
+		}
+}
+
+#[cfg(test)]
+mod test {
+	use rasn::types::{Class, Tag};
+
+	use super::{get_schema_kind, is_kind_tag, ASN1Schema};
+	use crate::asn1::ASN1Decoder;
+
+	#[test]
+	fn test_get_schema_kind() {
+		assert_eq!(get_schema_kind("seq").unwrap(), "sequence");
+		assert_eq!(get_schema_kind("oid").unwrap(), "oid");
+		assert!(get_schema_kind("other").is_err());
+	}
+
+	#[test]
+	fn test_is_kind_tag() {
+		assert!(is_kind_tag("integer", Tag::INTEGER));
+		assert!(is_kind_tag("string", Tag::UTF8_STRING));
+		assert!(is_kind_tag("context", Tag::new(Class::Context, 0)));
+		assert!(is_kind_tag("any", Tag::NULL));
+		assert!(!is_kind_tag("set", Tag::SEQUENCE));
+		assert!(!is_kind_tag("other", Tag::INTEGER));
+	}
+
+	#[test]
+	fn test_asn1_schema_matches() {
+		// SEQUENCE { INTEGER 1, OID 1.2.3, SEQUENCE { BOOLEAN true } }
+		let value = ASN1Decoder::new(hex::decode("300c02010106022a0330030101ff").expect("hex"));
+		let schema = ASN1Schema::Constructed(
+			"sequence",
+			Some(vec![
+				ASN1Schema::Kind("integer"),
+				ASN1Schema::Kind("oid"),
+				ASN1Schema::Constructed("sequence", None),
+			]),
+		);
+
+		assert!(schema.matches(&value));
+		assert!(ASN1Schema::Kind("any").matches(&value));
+		assert!(!ASN1Schema::Constructed("set", None).matches(&value));
+		assert!(!ASN1Schema::Constructed(
+			"sequence",
+			Some(vec![ASN1Schema::Kind("integer"), ASN1Schema::Kind("oid")])
+		)
+		.matches(&value));
+		assert!(!ASN1Schema::Constructed(
+			"sequence",
+			Some(vec![
+				ASN1Schema::Kind("integer"),
+				ASN1Schema::Kind("string"),
+				ASN1Schema::Kind("sequence"),
+			])
+		)
+		.matches(&value));
+
+		// A truncated SEQUENCE
+		let value = ASN1Decoder::new(vec![0x30, 0x03, 0x02, 0x01]);
+
+		assert!(!ASN1Schema::Constructed("sequence", None).matches(&value));
+	}
+}
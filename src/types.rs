@@ -1,5 +1,5 @@
 use anyhow::{bail, Error, Result};
-use chrono::{DateTime, Datelike, FixedOffset, Utc};
+use chrono::{DateTime, Datelike, FixedOffset, SecondsFormat, TimeZone, Utc};
 use napi::{
 	Env, JsArrayBuffer, JsBigInt, JsBoolean, JsBuffer, JsDate, JsNull, JsNumber, JsObject,
 	JsString, JsUndefined, JsUnknown, ValueType,
@@ -15,16 +15,25 @@ use rasn::{
 
 use crate::{
 	asn1::{ASN1Decoder, ASN1Iterator},
-	constants::{ASN1_OBJECT_DATE_KEY, ASN1_OBJECT_KIND_KEY, ASN1_OBJECT_TYPE_KEY},
+	constants::{
+		ASN1_OBJECT_BYTES_KEY, ASN1_OBJECT_DATE_KEY, ASN1_OBJECT_KIND_KEY, ASN1_OBJECT_NAME_KEY,
+		ASN1_OBJECT_OFFSET_KEY, ASN1_OBJECT_TYPE_KEY, ASN1_OBJECT_VALUE_KEY,
+		ASN1_UTC_TIME_CUTOFF_YEAR, ASN1_UTC_TIME_FIRST_YEAR,
+	},
 	get_big_int_from_integer, get_js_big_int_from_big_int, get_js_obj_from_asn_data,
 	get_js_obj_from_asn_object,
-	objects::{ASN1Date, ASN1Object, ASN1RawBitString, ASN1Struct, TypedObject, ASN1OID},
+	objects::{
+		ASN1BitString, ASN1ContextTag, ASN1Date, ASN1IntegerShim, ASN1Object, ASN1RawBitString,
+		ASN1SequenceShim, ASN1Set, ASN1SetOfShim, ASN1String, ASN1Struct, TypedObject, ASN1OID,
+	},
+	options::get_date_encoding_policy,
 	utils::{
 		get_array_buffer_from_js, get_array_from_js, get_asn_date_type_from_js_unknown,
 		get_asn_string_type_from_js_unknown, get_big_int_from_js, get_boolean_from_js,
 		get_buffer_from_js, get_integer_from_js, get_js_value_from_asn1_data,
-		get_utf16_from_string,
+		get_utf16_from_string, get_vec_from_js_unknown,
 	},
+	writer::encode_tlv,
 	ASN1NAPIError,
 };
 
@@ -77,15 +86,26 @@ pub enum ASN1Data {
 	Utf8String(UniversalString),
 	Bytes(Vec<u8>),
 	Array(Vec<ASN1Data>),
+	Set(Vec<ASN1Data>),
 	Object(ASN1Object),
-	UtcTime(DateTime<Utc>),
-	GeneralizedTime(DateTime<FixedOffset>),
+	Time {
+		instant: DateTime<FixedOffset>,
+		kind: ASN1TimeKind,
+	},
 	Unknown(Any),
 	#[rasn(tag(universal, 5))]
 	Null,
 	Undefined,
 }
 
+/// The alternatives of the Time CHOICE.
+#[derive(AsnType, Clone, Copy, Decode, Debug, Eq, PartialEq)]
+#[rasn(enumerated)]
+pub enum ASN1TimeKind {
+	Utc,
+	General,
+}
+
 /// Integer or Big Integer
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum ASN1Number {
@@ -93,7 +113,70 @@ pub enum ASN1Number {
 	BigInt(BigInt),
 }
 
+impl ASN1TimeKind {
+	/// Get the kind of time used by default for a date, which is a UTCTime
+	/// for the years 1950 through 2049 and a GeneralizedTime otherwise. The
+	/// date encoding policy in effect may lower the cutoff year or always
+	/// use a GeneralizedTime.
+	pub(crate) fn from_date<Tz: TimeZone>(date: &DateTime<Tz>) -> Self {
+		let policy = get_date_encoding_policy();
+		let cutoff_year = policy.cutoff_year.unwrap_or(ASN1_UTC_TIME_CUTOFF_YEAR);
+
+		if !policy.always_general.unwrap_or(false)
+			&& (ASN1_UTC_TIME_FIRST_YEAR..cutoff_year).contains(&date.with_timezone(&Utc).year())
+		{
+			ASN1TimeKind::Utc
+		} else {
+			ASN1TimeKind::General
+		}
+	}
+
+	/// Check that a date can be represented by this kind of time. The four
+	/// digit year of a GeneralizedTime ends at 9999, the year of the
+	/// `99991231235959Z` no-expiry date.
+	pub(crate) fn is_valid_for<Tz: TimeZone>(self, date: &DateTime<Tz>) -> bool {
+		match self {
+			ASN1TimeKind::Utc => (ASN1_UTC_TIME_FIRST_YEAR..ASN1_UTC_TIME_CUTOFF_YEAR)
+				.contains(&date.with_timezone(&Utc).year()),
+			ASN1TimeKind::General => (0..=9999).contains(&date.year()),
+		}
+	}
+
+	/// Get the kind of time from its name in a date object.
+	pub(crate) fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"utc" => Some(ASN1TimeKind::Utc),
+			"general" => Some(ASN1TimeKind::General),
+			_ => None,
+		}
+	}
+
+	/// Get the name of this kind of time in a date object.
+	pub(crate) fn get_name(self) -> &'static str {
+		match self {
+			ASN1TimeKind::Utc => "utc",
+			ASN1TimeKind::General => "general",
+		}
+	}
+
+	/// Get the tag of this kind of time.
+	pub(crate) fn get_tag(self) -> Tag {
+		match self {
+			ASN1TimeKind::Utc => Tag::UTC_TIME,
+			ASN1TimeKind::General => Tag::GENERALIZED_TIME,
+		}
+	}
+}
+
 impl ASN1Data {
+	/// Create a time of the kind used by default for the date.
+	pub(crate) fn new_time<Tz: TimeZone>(date: DateTime<Tz>) -> Self {
+		ASN1Data::Time {
+			kind: ASN1TimeKind::from_date(&date),
+			instant: date.fixed_offset(),
+		}
+	}
+
 	/// Converts `ASN1Data` to a `Vec<u8>`, handling all variants.
 	pub fn to_bytes(&self) -> Result<Vec<u8>> {
 		Ok(match self {
@@ -110,14 +193,38 @@ impl ASN1Data {
 			// 	.flat_map(|item| item.to_bytes()) // Flatten byte representations
 			// 	.collect(),
 			// ASN1Data::Object(obj) => obj.to_bytes(), // Assuming ASN1Object implements `to_bytes`
-			ASN1Data::UtcTime(dt) => dt.timestamp().to_be_bytes().to_vec(),
-			ASN1Data::GeneralizedTime(dt) => dt.timestamp().to_be_bytes().to_vec(),
+			ASN1Data::Time { instant, .. } => instant.timestamp().to_be_bytes().to_vec(),
 			ASN1Data::Unknown(any) => any.as_bytes().to_vec(), // Assuming `Any` provides `as_bytes`
 			ASN1Data::Null => vec![],
 			ASN1Data::Undefined => vec![],
 			_ => bail!(ASN1NAPIError::UnknownDateFormat),
 		})
 	}
+
+	/// Get the type string of the data as it is described to JavaScript.
+	pub(crate) fn get_type_name(&self) -> &'static str {
+		match self {
+			ASN1Data::Boolean(_) => "boolean",
+			ASN1Data::Integer(_) | ASN1Data::BigInt(_) => "integer",
+			ASN1Data::String(_)
+			| ASN1Data::PrintableString(_)
+			| ASN1Data::Ia5String(_)
+			| ASN1Data::Utf8String(_) => ASN1String::TYPE,
+			ASN1Data::Bytes(_) => "buffer",
+			ASN1Data::Array(_) => ASN1SequenceShim::TYPE,
+			ASN1Data::Set(_) => ASN1SetOfShim::TYPE,
+			ASN1Data::Object(ASN1Object::Oid(_)) => ASN1OID::TYPE,
+			ASN1Data::Object(ASN1Object::Set(_)) => ASN1Set::TYPE,
+			ASN1Data::Object(ASN1Object::String(_)) => ASN1String::TYPE,
+			ASN1Data::Object(ASN1Object::Date(_)) | ASN1Data::Time { .. } => ASN1Date::TYPE,
+			ASN1Data::Object(ASN1Object::BitString(_)) => ASN1BitString::TYPE,
+			ASN1Data::Object(ASN1Object::Struct(_)) => ASN1Struct::TYPE,
+			ASN1Data::Object(ASN1Object::Context(_)) => ASN1ContextTag::TYPE,
+			ASN1Data::Unknown(_) => "unknown",
+			ASN1Data::Null => "null",
+			ASN1Data::Undefined => "undefined",
+		}
+	}
 }
 
 impl From<Tag> for JsType {
@@ -135,7 +242,7 @@ impl From<Tag> for JsType {
 			Tag::GENERAL_STRING => JsType::String,
 			Tag::GRAPHIC_STRING => JsType::String,
 			Tag::VIDEOTEX_STRING => JsType::String,
-			Tag::TELETEX_STRING => JsType::String,
+			Tag::TELETEX_STRING => JsType::StringObject,
 			Tag::NUMERIC_STRING => JsType::String,
 			Tag::BMP_STRING => JsType::String,
 			Tag::BIT_STRING => JsType::Object,
@@ -170,16 +277,51 @@ impl TryFrom<ASN1Decoder> for ASN1Data {
 					ASN1Data::PrintableString(Implicit::new(value.into_string()?))
 				}
 				Tag::UTF8_STRING => ASN1Data::Utf8String(Implicit::new(value.into_string()?)),
+				Tag::TELETEX_STRING => match value.get_string_kind() {
+					"utf8" => ASN1Data::Utf8String(Implicit::new(value.into_string()?)),
+					kind => ASN1Data::Object(ASN1Object::String(ASN1String::new(
+						value.into_string()?,
+						Some(kind.to_string()),
+					))),
+				},
 				_ => bail!(ASN1NAPIError::UnknownStringFormat),
 			},
 			JsType::Buffer => ASN1Data::Bytes(value.into_bytes()?),
 			JsType::Sequence => ASN1Data::Array(Vec::<ASN1Data>::try_from(&value.into_iter())?),
-			JsType::Object => ASN1Data::Object(value.into_object()?),
-			JsType::DateTime => match *value.get_tag() {
-				Tag::UTC_TIME => ASN1Data::UtcTime(DateTime::<Utc>::from(value.into_date()?)),
-				Tag::GENERALIZED_TIME => {
-					ASN1Data::GeneralizedTime(DateTime::<FixedOffset>::from(value.into_date()?))
-				}
+			JsType::Object => match *value.get_tag() {
+				/* Any SET which is not an OID and value pair is a generic SET */
+				Tag::SET => match value.clone().into_object() {
+					Ok(object) => ASN1Data::Object(object),
+					Err(_) => ASN1Data::Set(Vec::<ASN1Data>::try_from(&value.into_set_iter()?)?),
+				},
+				_ => ASN1Data::Object(value.into_object()?),
+			},
+			JsType::DateTime => match value.get_options().date_format.as_deref() {
+				Some("iso") => ASN1Data::String(
+					value
+						.into_date()?
+						.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+				),
+				Some("asn1") => ASN1Data::String(value.get_time_string()?),
+				Some("date") | None => match *value.get_tag() {
+					Tag::UTC_TIME => ASN1Data::Time {
+						instant: value.into_date()?.into(),
+						kind: ASN1TimeKind::Utc,
+					},
+					Tag::GENERALIZED_TIME
+						if value.get_options().preserve_time_offset == Some(true) =>
+					{
+						ASN1Data::Time {
+							instant: value.get_date_time()?,
+							kind: ASN1TimeKind::General,
+						}
+					}
+					Tag::GENERALIZED_TIME => ASN1Data::Time {
+						instant: value.into_date()?.into(),
+						kind: ASN1TimeKind::General,
+					},
+					_ => bail!(ASN1NAPIError::UnknownDateFormat),
+				},
 				_ => bail!(ASN1NAPIError::UnknownDateFormat),
 			},
 			JsType::Unknown => ASN1Data::Unknown(value.into_any()?),
@@ -195,13 +337,19 @@ impl TryFrom<&Open> for ASN1Data {
 		Ok(match data.to_owned() {
 			Open::BmpString(data) => ASN1Data::String(data.to_string()),
 			Open::Bool(data) => ASN1Data::Boolean(data),
-			Open::GeneralizedTime(data) => ASN1Data::GeneralizedTime(data),
+			Open::GeneralizedTime(data) => ASN1Data::Time {
+				instant: data,
+				kind: ASN1TimeKind::General,
+			},
 			Open::Integer(data) => ASN1Data::BigInt(data),
 			Open::OctetString(data) => ASN1Data::Bytes(data.to_vec()),
 			Open::Ia5String(data) => ASN1Data::Ia5String(data),
 			Open::PrintableString(data) => ASN1Data::PrintableString(data),
 			Open::UniversalString(data) => ASN1Data::Utf8String(data),
-			Open::UtcTime(data) => ASN1Data::UtcTime(data),
+			Open::UtcTime(data) => ASN1Data::Time {
+				instant: data.into(),
+				kind: ASN1TimeKind::Utc,
+			},
 			Open::VisibleString(data) => ASN1Data::String(data.to_string()),
 			Open::InstanceOf(data) => ASN1Data::try_from(data.value)?,
 			Open::BitString(data) => {
@@ -247,6 +395,15 @@ impl TryFrom<JsUnknown> for ASN1Data {
 			ValueType::Object if value.is_array()? => ASN1Data::Array(get_array_from_js(value)?),
 			ValueType::Object => {
 				let object = value.coerce_to_object()?;
+
+				/* Unknown elements are decoded as an ArrayBuffer of their encoding, written verbatim */
+				if !object.has_named_property(ASN1_OBJECT_TYPE_KEY)? {
+					return match get_array_buffer_from_js(object.into_unknown()) {
+						Ok(data) => Ok(ASN1Data::Unknown(Any::new(data))),
+						Err(_) => bail!(ASN1NAPIError::UnknownObject),
+					};
+				}
+
 				if let Ok(object_type) = object.get_named_property::<JsString>(ASN1_OBJECT_TYPE_KEY)
 				{
 					let object_type = object_type.into_utf8()?.as_str()?.to_string();
@@ -255,6 +412,43 @@ impl TryFrom<JsUnknown> for ASN1Data {
 							object,
 						)?)));
 					}
+
+					/* Integers are written verbatim, even when not minimally encoded */
+					if object_type == ASN1IntegerShim::TYPE {
+						let bytes = get_vec_from_js_unknown(
+							object.get_named_property::<JsUnknown>(ASN1_OBJECT_BYTES_KEY)?,
+						)?;
+
+						if bytes.is_empty() {
+							bail!(ASN1NAPIError::InvalidDataEncoding);
+						}
+
+						return Ok(ASN1Data::Unknown(Any::new(encode_tlv(
+							Tag::INTEGER.value,
+							&bytes,
+						)?)));
+					}
+
+					/* Explicit constructed wrappers, a SET of an OID and value has a name */
+					if object_type == ASN1SequenceShim::TYPE
+						|| (object_type == ASN1SetOfShim::TYPE
+							&& !object.has_named_property(ASN1_OBJECT_NAME_KEY)?)
+					{
+						let values =
+							object.get_named_property::<JsUnknown>(ASN1_OBJECT_VALUE_KEY)?;
+
+						if !values.is_array()? {
+							bail!(ASN1NAPIError::UnknownObject);
+						}
+
+						let values = get_array_from_js(values)?;
+
+						return Ok(if object_type == ASN1SequenceShim::TYPE {
+							ASN1Data::Array(values)
+						} else {
+							ASN1Data::Set(values)
+						});
+					}
 				}
 
 				ASN1Data::Object(ASN1Object::try_from(object.into_unknown())?)
@@ -295,8 +489,14 @@ impl TryFrom<&ASN1Data> for Open {
 			ASN1Data::PrintableString(data) => Open::PrintableString(data),
 			ASN1Data::Ia5String(data) => Open::Ia5String(data),
 			ASN1Data::Utf8String(data) => Open::UniversalString(data),
-			ASN1Data::UtcTime(data) => Open::UtcTime(data),
-			ASN1Data::GeneralizedTime(data) => Open::GeneralizedTime(data),
+			ASN1Data::Time {
+				instant,
+				kind: ASN1TimeKind::Utc,
+			} => Open::UtcTime(instant.into()),
+			ASN1Data::Time {
+				instant,
+				kind: ASN1TimeKind::General,
+			} => Open::GeneralizedTime(instant),
 			ASN1Data::Bytes(data) => Open::OctetString(OctetString::from(data)),
 			ASN1Data::Object(data) => match data {
 				ASN1Object::BitString(data) => Open::BitString(data.into()),
@@ -339,11 +539,11 @@ impl TryFrom<(Env, ASN1Data)> for JsValue {
 				get_js_value_from_asn1_data(env, "Utf8String", &val.value)?
 			}
 			ASN1Data::Bytes(val) => JsValue::Buffer(env.create_buffer_with_data(val)?.into_raw()),
-			ASN1Data::UtcTime(val) => {
-				JsValue::DateTime(env.create_date(val.timestamp_millis() as f64)?)
-			}
-			ASN1Data::GeneralizedTime(val) => {
-				if val.year() < 2050 {
+			ASN1Data::Time { instant: val, kind } => {
+				let offset = val.offset().local_minus_utc();
+
+				/* A JS Date has no kind or offset, so a date object keeps them */
+				if kind != ASN1TimeKind::from_date(&val) || offset != 0 {
 					let mut obj = env.create_object()?;
 					obj.set_named_property::<JsString>(
 						ASN1_OBJECT_TYPE_KEY,
@@ -351,7 +551,7 @@ impl TryFrom<(Env, ASN1Data)> for JsValue {
 					)?;
 					obj.set_named_property::<JsString>(
 						ASN1_OBJECT_KIND_KEY,
-						env.create_string("general")?,
+						env.create_string(kind.get_name())?,
 					)?;
 
 					let timestamp_ms = val.timestamp_millis() as f64;
@@ -359,6 +559,14 @@ impl TryFrom<(Env, ASN1Data)> for JsValue {
 						ASN1_OBJECT_DATE_KEY,
 						env.create_date(timestamp_ms)?,
 					)?;
+
+					if offset != 0 {
+						obj.set_named_property::<JsNumber>(
+							ASN1_OBJECT_OFFSET_KEY,
+							env.create_int32(offset / 60)?,
+						)?;
+					}
+
 					JsValue::Object(obj)
 				} else {
 					JsValue::DateTime(env.create_date(val.timestamp_millis() as f64)?)
@@ -371,6 +579,18 @@ impl TryFrom<(Env, ASN1Data)> for JsValue {
 			ASN1Data::Array(val) => {
 				JsValue::Sequence(get_js_obj_from_asn_data(env, val.into_iter())?)
 			}
+			ASN1Data::Set(val) => {
+				let mut obj = env.create_object()?;
+				obj.set_named_property::<JsString>(
+					ASN1_OBJECT_TYPE_KEY,
+					env.create_string(ASN1SetOfShim::TYPE)?,
+				)?;
+				obj.set_named_property::<JsObject>(
+					ASN1_OBJECT_VALUE_KEY,
+					get_js_obj_from_asn_data(env, val.into_iter())?,
+				)?;
+				JsValue::Object(obj)
+			}
 			ASN1Data::Object(val) => JsValue::Object(get_js_obj_from_asn_object(env, val)?),
 			ASN1Data::Null => JsValue::Null(env.get_null()?),
 			ASN1Data::Undefined => JsValue::Undefined(env.get_undefined()?),
@@ -416,9 +636,15 @@ impl TryFrom<ASN1Decoder> for ASN1Number {
 
 #[cfg(test)]
 mod test {
+	use chrono::{TimeZone, Utc};
 	use num_bigint::BigInt;
 
-	use crate::{types::ASN1Number, ASN1Decoder};
+	use crate::{
+		asn1::ASN1Encoder,
+		options::{with_date_encoding_policy, ASN1DateEncodingPolicy},
+		types::{ASN1Data, ASN1Number, ASN1TimeKind},
+		ASN1Decoder,
+	};
 
 	#[test]
 	fn test_asn1number_try_from_asn1() {
@@ -435,4 +661,94 @@ mod test {
 			ASN1Number::BigInt(BigInt::from(18591708106338011145_i128))
 		);
 	}
+
+	#[test]
+	fn test_asn1_time_kind_from_date() {
+		for (year, kind) in [
+			(1949, ASN1TimeKind::General),
+			(1950, ASN1TimeKind::Utc),
+			(2049, ASN1TimeKind::Utc),
+			(2050, ASN1TimeKind::General),
+		] {
+			let date = Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).unwrap();
+
+			assert_eq!(ASN1TimeKind::from_date(&date), kind);
+			assert!(ASN1TimeKind::General.is_valid_for(&date));
+			assert_eq!(
+				ASN1TimeKind::Utc.is_valid_for(&date),
+				kind == ASN1TimeKind::Utc
+			);
+		}
+	}
+
+	#[test]
+	fn test_asn1_time_kind_with_policy() {
+		let date = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+		let policy = ASN1DateEncodingPolicy {
+			always_general: Some(true),
+			fractional_digits: Some(0),
+			..Default::default()
+		};
+		let data = with_date_encoding_policy(&policy, || ASN1Data::new_time(date));
+
+		assert_eq!(
+			with_date_encoding_policy(&policy, || rasn::ber::encode(&data)).unwrap(),
+			b"\x18\x0f20200101000000Z"
+		);
+		assert_eq!(ASN1TimeKind::from_date(&date), ASN1TimeKind::Utc);
+
+		let policy = ASN1DateEncodingPolicy {
+			cutoff_year: Some(2000),
+			..Default::default()
+		};
+
+		assert_eq!(
+			with_date_encoding_policy(&policy, || ASN1TimeKind::from_date(&date)),
+			ASN1TimeKind::General
+		);
+		assert!(ASN1TimeKind::Utc.is_valid_for(&date));
+	}
+
+	#[test]
+	fn test_asn1_time_kind_no_expiry() {
+		let date = Utc.with_ymd_and_hms(9999, 12, 31, 23, 59, 59).unwrap();
+		let data = ASN1Data::new_time(date);
+
+		assert_eq!(ASN1TimeKind::from_date(&date), ASN1TimeKind::General);
+		assert_eq!(
+			rasn::ber::encode(&data).unwrap(),
+			hex::decode("180f39393939313233313233353935395a").expect("hex")
+		);
+
+		let date = Utc.with_ymd_and_hms(10000, 1, 1, 0, 0, 0).unwrap();
+
+		assert!(!ASN1TimeKind::General.is_valid_for(&date));
+		assert!(rasn::ber::encode(&ASN1Data::new_time(date)).is_err());
+	}
+
+	#[test]
+	fn test_asn1_time_kind_round_trip() {
+		for (input, kind) in [
+			// UTCTime 220622181800Z
+			("170d3232303632323138313830305a", ASN1TimeKind::Utc),
+			// GeneralizedTime 20220622181800Z, which is in the UTCTime range
+			("180f32303232303632323138313830305a", ASN1TimeKind::General),
+			// GeneralizedTime 20600622181800Z
+			("180f32303630303632323138313830305a", ASN1TimeKind::General),
+		] {
+			let data = hex::decode(input).expect("hex");
+			let result = ASN1Data::try_from(ASN1Decoder::new(data.clone())).unwrap();
+
+			assert!(matches!(result, ASN1Data::Time { kind: decoded, .. } if decoded == kind));
+			assert_eq!(ASN1Encoder::new(result).encode().unwrap(), data);
+		}
+
+		let date = Utc.with_ymd_and_hms(2060, 1, 1, 0, 0, 0).unwrap();
+		let time = ASN1Data::Time {
+			instant: date.into(),
+			kind: ASN1TimeKind::Utc,
+		};
+
+		assert!(ASN1Encoder::new(time).encode().is_err());
+	}
 }
@@ -1,17 +1,25 @@
 use std::str::FromStr;
 
 use anyhow::{bail, Result};
-use chrono::{DateTime, Datelike, FixedOffset, NaiveDateTime, Utc};
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Timelike, Utc};
 use napi::{
 	bindgen_prelude::FromNapiValue, Env, JsArrayBuffer, JsBoolean, JsBuffer, JsDate, JsNumber,
 	JsString, JsUnknown, ValueType,
 };
 use num_bigint::{BigInt, Sign};
 use rasn::{ber::de::DecoderOptions, types::Utf8String, Decode, Tag};
+use sha2::{Digest, Sha256, Sha384, Sha512};
 
 use crate::{
-	constants::{ASN1_DATE_TIME_GENERAL_FORMAT_WITH_MS, ASN1_DATE_TIME_UTC_FORMAT},
+	constants::{
+		ASN1_DATE_TIME_GENERAL_FORMAT, ASN1_DATE_TIME_GENERAL_FORMAT_NO_SECONDS,
+		ASN1_DATE_TIME_GENERAL_FORMAT_OFFSET, ASN1_DATE_TIME_GENERAL_FORMAT_WITH_FRACTION,
+		ASN1_DATE_TIME_GENERAL_FORMAT_WITH_FRACTION_OFFSET, ASN1_DATE_TIME_GENERAL_FORMAT_WITH_MS,
+		ASN1_DATE_TIME_GENERAL_FORMAT_WITH_MS_OFFSET, ASN1_DATE_TIME_UTC_FORMAT,
+		ASN1_DATE_TIME_UTC_FORMAT_NO_SECONDS, ASN1_DATE_TIME_UTC_FORMAT_OFFSET,
+	},
 	get_js_obj_from_asn_string,
+	options::get_date_encoding_policy,
 	types::{ASN1Data, JsValue},
 	ASN1NAPIError,
 };
@@ -21,6 +29,33 @@ pub(crate) fn get_utf16_from_string<T: AsRef<str>>(value: T) -> Vec<u16> {
 	value.as_ref().encode_utf16().collect::<Vec<u16>>()
 }
 
+/// Get a string from big endian utf16 bytes.
+pub(crate) fn get_string_from_utf16_be(data: &[u8]) -> Result<String> {
+	if data.len() % 2 != 0 {
+		bail!(ASN1NAPIError::InvalidStringEncoding);
+	}
+
+	let units = data
+		.chunks_exact(2)
+		.map(|unit| u16::from_be_bytes([unit[0], unit[1]]))
+		.collect::<Vec<u16>>();
+
+	match String::from_utf16(&units) {
+		Ok(value) => Ok(value),
+		Err(_) => bail!(ASN1NAPIError::InvalidStringEncoding),
+	}
+}
+
+/// Get a string from Latin-1 bytes.
+pub(crate) fn get_string_from_latin1(data: &[u8]) -> String {
+	data.iter().map(|&byte| char::from(byte)).collect()
+}
+
+/// Get Latin-1 bytes from a string, if every character can be represented.
+pub(crate) fn get_latin1_from_string(data: &str) -> Option<Vec<u8>> {
+	data.chars().map(|c| u8::try_from(c).ok()).collect()
+}
+
 /// Get a Vec<u32> of the numbers in an OID string.
 pub(crate) fn get_oid_elements_from_string<T: AsRef<str>>(value: T) -> Result<Vec<u32>> {
 	value
@@ -47,36 +82,118 @@ pub(crate) fn get_words_from_big_int(data: BigInt) -> (bool, Vec<u64>) {
 	(sign == Sign::Minus, words)
 }
 
+/// Get the time string of an ASN.1 encoded UTCTime or GeneralizedTime.
+pub(crate) fn get_time_string_from_asn1<T: AsRef<[u8]>>(data: T) -> Result<String> {
+	let mut decoder = rasn::ber::de::Decoder::new(data.as_ref(), DecoderOptions::ber());
+	let decoded = match data.as_ref().first().unwrap_or(&0) {
+		0x17 => Utf8String::decode_with_tag(&mut decoder, Tag::UTC_TIME),
+		0x18 => Utf8String::decode_with_tag(&mut decoder, Tag::GENERALIZED_TIME),
+		_ => bail!(ASN1NAPIError::MalformedData),
+	};
+
+	if let Ok(decoded) = decoded {
+		Ok(decoded)
+	} else {
+		bail!(ASN1NAPIError::MalformedData)
+	}
+}
+
 /// Helper for handling date/times with milliseconds
 /// TODO rasn library does not properly handle dates with milliseconds.
-#[allow(deprecated)]
 pub(crate) fn get_utc_date_time_from_asn1_milli<T: AsRef<[u8]>>(data: T) -> Result<DateTime<Utc>> {
-	let mut decoder = rasn::ber::de::Decoder::new(data.as_ref(), DecoderOptions::ber());
-	let (decoded, format) = match data.as_ref().first().unwrap_or(&0) {
+	Ok(get_date_time_from_asn1(data)?.with_timezone(&Utc))
+}
+
+/// Get the date/time of an ASN.1 encoded UTCTime or GeneralizedTime, keeping
+/// the time zone offset when there is one.
+#[allow(deprecated)]
+pub(crate) fn get_date_time_from_asn1<T: AsRef<[u8]>>(data: T) -> Result<DateTime<FixedOffset>> {
+	/* Seconds may be omitted, in which case they default to 0 */
+	let (formats, offset_format) = match data.as_ref().first().unwrap_or(&0) {
 		0x17 => (
-			Utf8String::decode_with_tag(&mut decoder, Tag::UTC_TIME),
-			ASN1_DATE_TIME_UTC_FORMAT,
+			[
+				ASN1_DATE_TIME_UTC_FORMAT,
+				ASN1_DATE_TIME_UTC_FORMAT_NO_SECONDS,
+			],
+			ASN1_DATE_TIME_UTC_FORMAT_OFFSET,
 		),
 		0x18 => (
-			Utf8String::decode_with_tag(&mut decoder, Tag::GENERALIZED_TIME),
-			ASN1_DATE_TIME_GENERAL_FORMAT_WITH_MS,
+			[
+				ASN1_DATE_TIME_GENERAL_FORMAT_WITH_FRACTION,
+				ASN1_DATE_TIME_GENERAL_FORMAT_NO_SECONDS,
+			],
+			ASN1_DATE_TIME_GENERAL_FORMAT_WITH_FRACTION_OFFSET,
 		),
 		_ => bail!(ASN1NAPIError::MalformedData),
 	};
+	let decoded = get_time_string_from_asn1(data)?;
 
-	if let Ok(decoded) = decoded {
-		if let Some(offset) = FixedOffset::east_opt(0) {
-			Ok(DateTime::<FixedOffset>::from_utc(
-				NaiveDateTime::parse_from_str(&decoded, format)?,
-				offset,
-			)
-			.with_timezone(&Utc))
+	let mut result = NaiveDateTime::parse_from_str(&decoded, formats[0]);
+	if result.is_err() {
+		result = NaiveDateTime::parse_from_str(&decoded, formats[1]);
+	}
+
+	match (result, FixedOffset::east_opt(0)) {
+		(Ok(result), Some(offset)) => Ok(DateTime::<FixedOffset>::from_utc(result, offset)),
+		(Err(error), _) => {
+			DateTime::parse_from_str(&decoded, offset_format).map_err(|_| error.into())
+		}
+		_ => bail!(ASN1NAPIError::MalformedData),
+	}
+}
+
+/// Get the nanoseconds since the epoch of an ASN.1 encoded UTCTime or
+/// GeneralizedTime, keeping the full precision of the fraction of a second.
+pub(crate) fn get_time_nanos_from_asn1<T: AsRef<[u8]>>(data: T) -> Result<BigInt> {
+	let date = get_date_time_from_asn1(data)?;
+
+	Ok(BigInt::from(date.timestamp()) * 1_000_000_000 + date.timestamp_subsec_nanos())
+}
+
+/// Get the GeneralizedTime string of a date/time, with milliseconds when
+/// they are not 0 and with the time zone offset when it is not UTC. The
+/// date encoding policy in effect may limit the number of digits of the
+/// fraction of a second or always use UTC.
+pub(crate) fn get_generalized_time_string(date: &DateTime<FixedOffset>) -> String {
+	let policy = get_date_encoding_policy();
+	let date = &match policy.preserve_offset {
+		Some(false) => date.with_timezone(&Utc).fixed_offset(),
+		_ => *date,
+	};
+
+	if let Some(digits) = policy.fractional_digits {
+		let mut time = date.format("%Y%m%d%H%M%S").to_string();
+
+		/* The nanoseconds exceed a second only during a leap second */
+		let fraction = format!("{:09}", date.nanosecond() % 1_000_000_000);
+		let fraction = fraction[..digits.min(9) as usize].trim_end_matches('0');
+
+		/* DER forbids trailing zeros and an empty fraction */
+		if !fraction.is_empty() {
+			time.push('.');
+			time.push_str(fraction);
+		}
+
+		if date.offset().local_minus_utc() == 0 {
+			time.push('Z');
 		} else {
-			bail!(ASN1NAPIError::MalformedData)
+			time.push_str(&date.format("%z").to_string());
 		}
-	} else {
-		bail!(ASN1NAPIError::MalformedData)
+
+		return time;
 	}
+
+	let format = match (
+		date.timestamp_millis() % 1000 == 0,
+		date.offset().local_minus_utc() == 0,
+	) {
+		(true, true) => ASN1_DATE_TIME_GENERAL_FORMAT,
+		(false, true) => ASN1_DATE_TIME_GENERAL_FORMAT_WITH_MS,
+		(true, false) => ASN1_DATE_TIME_GENERAL_FORMAT_OFFSET,
+		(false, false) => ASN1_DATE_TIME_GENERAL_FORMAT_WITH_MS_OFFSET,
+	};
+
+	date.format(format).to_string()
 }
 
 /// Get an chrono datetime from a JsUnknown.
@@ -175,12 +292,7 @@ pub(crate) fn get_asn_string_type_from_js_unknown(data: JsUnknown) -> Result<ASN
 
 /// Get an ASN1Data Date from a JsUnknown.
 pub(crate) fn get_asn_date_type_from_js_unknown(data: JsUnknown) -> Result<ASN1Data> {
-	let date = get_fixed_date_from_js(data)?;
-	if date.year() < 2050 {
-		Ok(ASN1Data::UtcTime(date.to_utc()))
-	} else {
-		Ok(ASN1Data::GeneralizedTime(date))
-	}
+	Ok(ASN1Data::new_time(get_fixed_date_from_js(data)?))
 }
 
 /// Get an JsValue String from an ASN1Data.
@@ -232,7 +344,7 @@ pub(crate) fn is_printable_string(data: &str) -> bool {
 
 /// Check if a string is an IA5 string.
 pub(crate) fn is_ia5_string(data: &str) -> bool {
-	data.chars().all(|c| c.is_ascii())
+	data.is_ascii()
 }
 
 /// Return the kind of string this should be encoded as
@@ -251,6 +363,7 @@ pub(crate) fn convert_string_kind_to_tag(kind: &str) -> Result<Tag> {
 		"printable" => Tag::PRINTABLE_STRING,
 		"ia5" => Tag::IA5_STRING,
 		"utf8" => Tag::UTF8_STRING,
+		"teletex" => Tag::TELETEX_STRING,
 		_ => bail!(ASN1NAPIError::UnknownStringFormat),
 	})
 }
@@ -260,13 +373,31 @@ pub(crate) fn get_string_kind_from_tag(tag: Tag) -> &'static str {
 		Tag::PRINTABLE_STRING => "printable",
 		Tag::IA5_STRING => "ia5",
 		Tag::UTF8_STRING => "utf8",
+		Tag::TELETEX_STRING => "teletex",
 		_ => "unknown",
 	}
 }
 
-/// The "rasn" library authors forgot to include a way to get the header
-/// length for a tag, so we must re-implement ASN.1 BER parsing here.
-pub(crate) fn header_length(data: &[u8]) -> Result<usize, &'static str> {
+/// Check if a tag is one of the character string types.
+pub(crate) fn is_string_tag(tag: Tag) -> bool {
+	matches!(
+		tag,
+		Tag::PRINTABLE_STRING
+			| Tag::IA5_STRING
+			| Tag::UTF8_STRING
+			| Tag::TELETEX_STRING
+			| Tag::BMP_STRING
+			| Tag::GENERAL_STRING
+			| Tag::GRAPHIC_STRING
+			| Tag::VIDEOTEX_STRING
+			| Tag::VISIBLE_STRING
+			| Tag::NUMERIC_STRING
+			| Tag::UNIVERSAL_STRING
+	)
+}
+
+/// Get the length of the tag field of the first element.
+pub(crate) fn tag_length(data: &[u8]) -> Result<usize, &'static str> {
 	let mut pos = 0;
 	if data.is_empty() {
 		return Err("data too short for tag");
@@ -289,6 +420,14 @@ pub(crate) fn header_length(data: &[u8]) -> Result<usize, &'static str> {
 		}
 	}
 
+	Ok(pos)
+}
+
+/// The "rasn" library authors forgot to include a way to get the header
+/// length for a tag, so we must re-implement ASN.1 BER parsing here.
+pub(crate) fn header_length(data: &[u8]) -> Result<usize, &'static str> {
+	let mut pos = tag_length(data)?;
+
 	// Ensure there's at least one byte for the length field.
 	if pos >= data.len() {
 		return Err("data too short for length field");
@@ -309,17 +448,108 @@ pub(crate) fn header_length(data: &[u8]) -> Result<usize, &'static str> {
 	Ok(pos)
 }
 
+/// Get the declared length of the contents of the first element.
+pub(crate) fn content_length(data: &[u8]) -> Result<usize, &'static str> {
+	let pos = tag_length(data)?;
+	let header = header_length(data)?;
+	let length_byte = data[pos];
+
+	if length_byte & 0x80 == 0 {
+		return Ok(length_byte as usize);
+	} else if length_byte == 0x80 {
+		return Err("indefinite length is not supported");
+	}
+
+	data[pos + 1..header]
+		.iter()
+		.try_fold(0_usize, |length, &byte| {
+			length
+				.checked_mul(0x100)
+				.and_then(|length| length.checked_add(byte as usize))
+				.ok_or("length too large")
+		})
+}
+
+/// Split concatenated ASN.1 elements into the raw bytes of each element.
+pub(crate) fn split_elements(data: &[u8]) -> Result<Vec<&[u8]>, &'static str> {
+	let mut elements = Vec::new();
+	let mut rest = data;
+
+	while !rest.is_empty() {
+		let length = header_length(rest)?
+			.checked_add(content_length(rest)?)
+			.ok_or("length too large")?;
+
+		if length > rest.len() {
+			return Err("data too short for contents");
+		}
+
+		let (element, remaining) = rest.split_at(length);
+		elements.push(element);
+		rest = remaining;
+	}
+
+	Ok(elements)
+}
+
+/// Get the digest of data with a SHA-2 digest algorithm name, which is one
+/// of "sha256", "sha384" or "sha512".
+pub(crate) fn get_digest<T: AsRef<str>>(algorithm: T, data: &[u8]) -> Result<Vec<u8>> {
+	match algorithm.as_ref() {
+		"sha256" => Ok(Sha256::digest(data).to_vec()),
+		"sha384" => Ok(Sha384::digest(data).to_vec()),
+		"sha512" => Ok(Sha512::digest(data).to_vec()),
+		_ => bail!(ASN1NAPIError::InvalidDigestAlgorithm),
+	}
+}
+
+/// Get the Unicode form of a domain, decoding any "xn--" Punycode labels.
+/// Domains which cannot be decoded have no Unicode form.
+pub(crate) fn get_unicode_domain(domain: &str) -> Option<String> {
+	match idna::domain_to_unicode(domain) {
+		(value, Ok(())) => Some(value),
+		(_, Err(_)) => None,
+	}
+}
+
 #[cfg(test)]
 mod test {
-	use chrono::{TimeZone, Utc};
+	use chrono::{FixedOffset, TimeZone, Utc};
 	use num_bigint::BigInt;
 
-	use crate::utils::get_utf16_from_string;
+	use crate::utils::{
+		get_latin1_from_string, get_string_from_latin1, get_string_from_utf16_be,
+		get_utf16_from_string,
+	};
 
+	use super::content_length;
 	use super::get_oid_elements_from_string;
 	use super::get_string_from_oid_elements;
+	use super::get_time_nanos_from_asn1;
 	use super::get_utc_date_time_from_asn1_milli;
 	use super::get_words_from_big_int;
+	use super::split_elements;
+	use super::{get_date_time_from_asn1, get_generalized_time_string};
+	use super::{get_digest, get_unicode_domain};
+	use crate::options::{with_date_encoding_policy, ASN1DateEncodingPolicy};
+
+	#[test]
+	fn test_get_string_from_utf16_be() {
+		assert_eq!(
+			get_string_from_utf16_be(&[0x00, 0x74, 0x00, 0x65, 0xd8, 0x3d, 0xde, 0x00]).unwrap(),
+			"te\u{1f600}"
+		);
+		assert!(get_string_from_utf16_be(&[0x00, 0x74, 0x00]).is_err());
+	}
+
+	#[test]
+	fn test_get_string_from_latin1() {
+		let latin1 = vec![0x63, 0x61, 0x66, 0xe9];
+
+		assert_eq!(get_string_from_latin1(&latin1), "caf\u{e9}");
+		assert_eq!(get_latin1_from_string("caf\u{e9}"), Some(latin1));
+		assert_eq!(get_latin1_from_string("\u{1133}"), None);
+	}
 
 	#[test]
 	fn test_get_utf16_from_string() {
@@ -372,5 +602,170 @@ mod test {
 		];
 
 		assert_eq!(get_utc_date_time_from_asn1_milli(input).unwrap(), date);
+
+		// UTCTime 2206221818Z and GeneralizedTime 202206221818Z without seconds
+		let date = Utc.with_ymd_and_hms(2022, 6, 22, 18, 18, 0).unwrap();
+		let input = b"\x17\x0b2206221818Z";
+
+		assert_eq!(get_utc_date_time_from_asn1_milli(input).unwrap(), date);
+
+		let input = b"\x18\x0d202206221818Z";
+
+		assert_eq!(get_utc_date_time_from_asn1_milli(input).unwrap(), date);
+	}
+
+	#[test]
+	fn test_get_date_time_from_asn1_offset() {
+		let input = b"\x18\x1320220926100000+0800";
+		let date = get_date_time_from_asn1(input).unwrap();
+
+		assert_eq!(date.offset().local_minus_utc(), 8 * 3600);
+		assert_eq!(
+			date.with_timezone(&Utc),
+			Utc.with_ymd_and_hms(2022, 9, 26, 2, 0, 0).unwrap()
+		);
+		assert_eq!(get_generalized_time_string(&date), "20220926100000+0800");
+		assert_eq!(
+			get_generalized_time_string(&date.with_timezone(&FixedOffset::east_opt(0).unwrap())),
+			"20220926020000Z"
+		);
+		assert!(get_date_time_from_asn1(b"\x18\x0f2022092610000+08").is_err());
+	}
+
+	#[test]
+	fn test_get_generalized_time_string_with_policy() {
+		let date = get_date_time_from_asn1(b"\x18\x1a20220926100000.123456+0800").unwrap();
+		let policy = |fractional_digits, preserve_offset| ASN1DateEncodingPolicy {
+			fractional_digits,
+			preserve_offset,
+			..Default::default()
+		};
+
+		assert_eq!(
+			get_generalized_time_string(&date),
+			"20220926100000.123+0800"
+		);
+		assert_eq!(
+			with_date_encoding_policy(&policy(Some(6), None), || {
+				get_generalized_time_string(&date)
+			}),
+			"20220926100000.123456+0800"
+		);
+		assert_eq!(
+			with_date_encoding_policy(&policy(Some(0), Some(false)), || {
+				get_generalized_time_string(&date)
+			}),
+			"20220926020000Z"
+		);
+		assert_eq!(
+			with_date_encoding_policy(&policy(Some(9), Some(false)), || {
+				get_generalized_time_string(&date)
+			}),
+			"20220926020000.123456Z"
+		);
+		assert_eq!(
+			with_date_encoding_policy(&policy(Some(2), Some(false)), || {
+				get_generalized_time_string(&date)
+			}),
+			"20220926020000.12Z"
+		);
+
+		let date = get_date_time_from_asn1(b"\x18\x1320220926100000.000Z").unwrap();
+
+		assert_eq!(
+			with_date_encoding_policy(&policy(Some(3), None), || {
+				get_generalized_time_string(&date)
+			}),
+			"20220926100000Z"
+		);
+	}
+
+	#[test]
+	fn test_get_time_nanos_from_asn1() {
+		assert_eq!(
+			get_time_nanos_from_asn1(b"\x18\x1620220926100000.123456Z").unwrap(),
+			BigInt::from(1664186400123456000_i64)
+		);
+		assert_eq!(
+			get_time_nanos_from_asn1(b"\x18\x1920220926100000.123456789Z").unwrap(),
+			BigInt::from(1664186400123456789_i64)
+		);
+		assert_eq!(
+			get_time_nanos_from_asn1(b"\x18\x1a20220926100000.123456+0800").unwrap(),
+			BigInt::from(1664157600123456000_i64)
+		);
+		assert_eq!(
+			get_time_nanos_from_asn1(b"\x17\x0d220926100000Z").unwrap(),
+			BigInt::from(1664186400000000000_i64)
+		);
+		assert_eq!(
+			get_utc_date_time_from_asn1_milli(b"\x18\x1620220926100000.123456Z")
+				.unwrap()
+				.timestamp_millis(),
+			1664186400123
+		);
+	}
+
+	#[test]
+	fn test_get_digest() {
+		let digest = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+		assert_eq!(hex::encode(get_digest("sha256", b"abc").unwrap()), digest);
+		assert_eq!(get_digest("sha384", b"abc").unwrap().len(), 48);
+		assert_eq!(
+			hex::encode(&get_digest("sha512", b"abc").unwrap()[..8]),
+			"ddaf35a193617aba"
+		);
+		assert!(get_digest("2.16.840.1.101.3.4.2.1", b"abc").is_err());
+		assert!(get_digest("sha3-256", b"abc").is_err());
+		assert!(get_digest("notAnOid", b"abc").is_err());
+	}
+
+	#[test]
+	fn test_get_unicode_domain() {
+		assert_eq!(
+			get_unicode_domain("mail.xn--bcher-kva.example").unwrap(),
+			"mail.bücher.example"
+		);
+		assert_eq!(
+			get_unicode_domain("XN--MNCHEN-3YA.de").unwrap(),
+			"münchen.de"
+		);
+		assert_eq!(get_unicode_domain("example.com").unwrap(), "example.com");
+		assert!(get_unicode_domain("xn--bcher-kva!.example").is_none());
+		assert!(get_unicode_domain("xn--99999999999.example").is_none());
+		// A label decoding to only ASCII could be used to spoof a domain
+		assert!(get_unicode_domain("xn--example-.com").is_none());
+	}
+
+	#[test]
+	fn test_content_length() {
+		assert_eq!(content_length(&[0x30, 0x00]).unwrap(), 0);
+		assert_eq!(content_length(&[0x04, 0x03, 0x01, 0x02, 0x03]).unwrap(), 3);
+		assert_eq!(content_length(&[0x30, 0x82, 0x01, 0x2c]).unwrap(), 300);
+		assert!(content_length(&[0x30, 0x80]).is_err());
+		assert!(content_length(&[0x30, 0x89, 0x01, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+	}
+
+	#[test]
+	#[cfg(target_pointer_width = "64")]
+	fn test_content_length_beyond_u32() {
+		// A declared 5GB OCTET STRING with truncated contents
+		let data = [0x04, 0x85, 0x01, 0x40, 0x00, 0x00, 0x00, 0x00];
+
+		assert_eq!(content_length(&data).unwrap(), 5 << 30);
+		assert!(split_elements(&data).is_err());
+	}
+
+	#[test]
+	fn test_split_elements() {
+		let input = [0x02, 0x01, 0x01, 0x30, 0x00, 0x04, 0x02, 0x01, 0x02];
+
+		assert_eq!(
+			split_elements(&input).unwrap(),
+			vec![&input[0..3], &input[3..5], &input[5..9]]
+		);
+		assert!(split_elements(&input[..8]).is_err());
+		assert!(split_elements(&[]).unwrap().is_empty());
 	}
 }
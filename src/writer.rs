@@ -0,0 +1,475 @@
+use anyhow::{bail, Result};
+use napi::bindgen_prelude::Buffer;
+use rasn::types::{Class, Tag};
+
+use crate::{
+	objects::{ASN1Context, ASN1Object},
+	types::ASN1Data,
+	ASN1NAPIError,
+};
+
+/// Element with an explicit tag class and number, encoded exactly as given
+/// without any tag inference. Either the contents of a primitive element or
+/// the elements of a constructed element must be provided.
+#[napi(object, js_name = "ASN1RawElement")]
+pub struct ASN1RawElement {
+	#[napi(ts_type = "'universal' | 'application' | 'context' | 'private'")]
+	pub class: String,
+	pub tag: u32,
+	pub contents: Option<Buffer>,
+	pub elements: Option<Vec<ASN1RawElement>>,
+}
+
+/// Encoder writing constructed types into a single output buffer. The rasn
+/// encoder allocates a new buffer for every nesting level, so instead the
+/// content lengths are computed in a first pass and the data is written in
+/// a second pass. Primitive values are still encoded by rasn.
+#[derive(Default)]
+struct ASN1Writer {
+	lengths: Vec<usize>,
+	primitives: Vec<Vec<u8>>,
+}
+
+/// A constructed value which is written directly by the writer.
+enum Constructed<'a> {
+	Sequence(&'a [ASN1Data]),
+	Set(&'a [ASN1Data]),
+	Explicit(Tag, &'a ASN1Data),
+}
+
+impl<'a> Constructed<'a> {
+	fn from_data(data: &'a ASN1Data) -> Option<Self> {
+		match data {
+			ASN1Data::Array(values) => Some(Self::Sequence(values)),
+			ASN1Data::Set(values) => Some(Self::Set(values)),
+			ASN1Data::Object(ASN1Object::Context(ASN1Context {
+				value,
+				contains,
+				kind,
+			})) if kind == "explicit" => Some(Self::Explicit(Tag::new(Class::Context, *value), contains)),
+			_ => None,
+		}
+	}
+
+	fn tag(&self) -> Tag {
+		match self {
+			Self::Sequence(_) => Tag::SEQUENCE,
+			Self::Set(_) => Tag::SET,
+			Self::Explicit(tag, _) => *tag,
+		}
+	}
+
+	fn values(&self) -> &'a [ASN1Data] {
+		match self {
+			Self::Sequence(values) | Self::Set(values) => values,
+			Self::Explicit(_, value) => std::slice::from_ref(*value),
+		}
+	}
+}
+
+impl ASN1Writer {
+	/// Compute the encoded length of the data, recording the content length
+	/// of each constructed value in the order they will be written.
+	fn measure(&mut self, data: &ASN1Data) -> Result<usize> {
+		if let Some(constructed) = Constructed::from_data(data) {
+			let index = self.lengths.len();
+			self.lengths.push(0);
+
+			let mut length = 0_usize;
+
+			for value in constructed.values() {
+				length = length
+					.checked_add(self.measure(value)?)
+					.ok_or(ASN1NAPIError::InvalidDataEncoding)?;
+			}
+
+			self.lengths[index] = length;
+
+			get_element_length(get_identifier_length(constructed.tag()), length)
+		} else if let Ok(encoded) = rasn::ber::encode(data) {
+			let length = encoded.len();
+			self.primitives.push(encoded);
+
+			Ok(length)
+		} else {
+			bail!(ASN1NAPIError::InvalidDataEncoding)
+		}
+	}
+
+	/// Write the data measured in the first pass to the output.
+	fn write(
+		&self,
+		data: &ASN1Data,
+		output: &mut Vec<u8>,
+		lengths: &mut usize,
+		primitives: &mut usize,
+	) {
+		if let Some(constructed) = Constructed::from_data(data) {
+			write_identifier(constructed.tag(), true, output);
+			write_length(self.lengths[*lengths], output);
+			*lengths += 1;
+
+			let start = output.len();
+			let mut ends = Vec::new();
+
+			for value in constructed.values() {
+				self.write(value, output, lengths, primitives);
+
+				if let Constructed::Set(_) = constructed {
+					ends.push(output.len());
+				}
+			}
+
+			if let Constructed::Set(_) = constructed {
+				sort_elements(output, start, &ends);
+			}
+		} else {
+			output.extend_from_slice(&self.primitives[*primitives]);
+			*primitives += 1;
+		}
+	}
+}
+
+/// Encode ASN1Data into a single buffer sized by a first measuring pass.
+pub(crate) fn encode(data: &ASN1Data) -> Result<Vec<u8>> {
+	let mut writer = ASN1Writer::default();
+	let mut output = Vec::with_capacity(writer.measure(data)?);
+
+	writer.write(data, &mut output, &mut 0, &mut 0);
+
+	Ok(output)
+}
+
+/// Sort the elements written to the output from the start offset, each
+/// ending at one of the end offsets, by their encodings as DER requires for
+/// the elements of a SET OF.
+fn sort_elements(output: &mut [u8], start: usize, ends: &[usize]) {
+	let mut elements = ends
+		.iter()
+		.scan(start, |offset, &end| {
+			let element = &output[*offset..end];
+			*offset = end;
+
+			Some(element)
+		})
+		.collect::<Vec<&[u8]>>();
+
+	if elements.windows(2).all(|pair| pair[0] <= pair[1]) {
+		return;
+	}
+
+	elements.sort();
+
+	let sorted = elements.concat();
+	output[start..start + sorted.len()].copy_from_slice(&sorted);
+}
+
+/// Encode a TLV from a single identifier octet, which may be of any class
+/// and either primitive or constructed, and the contents.
+pub(crate) fn encode_tlv(tag: u32, content: &[u8]) -> Result<Vec<u8>> {
+	// A tag number of 31 marks the high tag number form
+	let tag = match u8::try_from(tag) {
+		Ok(tag) if tag & 0x1F != 0x1F => tag,
+		_ => bail!(ASN1NAPIError::InvalidTag),
+	};
+
+	let mut output = Vec::with_capacity(get_element_length(1, content.len())?);
+
+	output.push(tag);
+	write_length(content.len(), &mut output);
+	output.extend_from_slice(content);
+
+	Ok(output)
+}
+
+/// Encode an element with an explicit tag exactly as given.
+pub(crate) fn encode_raw(element: &ASN1RawElement) -> Result<Vec<u8>> {
+	let class = match element.class.as_str() {
+		"universal" => Class::Universal,
+		"application" => Class::Application,
+		"context" => Class::Context,
+		"private" => Class::Private,
+		_ => bail!(ASN1NAPIError::InvalidTag),
+	};
+
+	let (constructed, contents) = match (&element.contents, &element.elements) {
+		(Some(contents), None) => (false, contents.to_vec()),
+		(None, Some(elements)) => (
+			true,
+			elements
+				.iter()
+				.map(encode_raw)
+				.collect::<Result<Vec<Vec<u8>>>>()?
+				.concat(),
+		),
+		_ => bail!(ASN1NAPIError::InvalidDataEncoding),
+	};
+
+	let tag = Tag::new(class, element.tag);
+	let mut output = Vec::with_capacity(get_element_length(
+		get_identifier_length(tag),
+		contents.len(),
+	)?);
+
+	write_identifier(tag, constructed, &mut output);
+	write_length(contents.len(), &mut output);
+	output.extend_from_slice(&contents);
+
+	Ok(output)
+}
+
+/// Get the length of the identifier octets for a tag.
+fn get_identifier_length(tag: Tag) -> usize {
+	if tag.value < 0x1F {
+		1
+	} else {
+		1 + get_base128_length(tag.value)
+	}
+}
+
+/// Write the identifier octets for a tag.
+fn write_identifier(tag: Tag, constructed: bool, output: &mut Vec<u8>) {
+	let class = match tag.class {
+		Class::Universal => 0x00,
+		Class::Application => 0x40,
+		Class::Context => 0x80,
+		Class::Private => 0xC0,
+	} | if constructed { 0x20 } else { 0x00 };
+
+	if tag.value < 0x1F {
+		output.push(class | tag.value as u8);
+	} else {
+		output.push(class | 0x1F);
+
+		for index in (0..get_base128_length(tag.value)).rev() {
+			let byte = ((tag.value >> (index * 7)) & 0x7F) as u8;
+			output.push(if index == 0 { byte } else { byte | 0x80 });
+		}
+	}
+}
+
+/// Get the number of base 128 digits needed for a high tag number.
+fn get_base128_length(value: u32) -> usize {
+	((32 - value.leading_zeros() as usize + 6) / 7).max(1)
+}
+
+/// Get the length of the definite length octets.
+fn get_length_length(length: usize) -> usize {
+	if length < 0x80 {
+		1
+	} else {
+		1 + (usize::BITS as usize - length.leading_zeros() as usize + 7) / 8
+	}
+}
+
+/// Get the length of an encoded element from the length of its identifier
+/// octets and contents. Lengths beyond the largest possible allocation are
+/// rejected rather than overflowing, as the element could never be written.
+fn get_element_length(identifier_length: usize, length: usize) -> Result<usize> {
+	match identifier_length
+		.checked_add(get_length_length(length))
+		.and_then(|header| header.checked_add(length))
+	{
+		Some(total) if total <= isize::MAX as usize => Ok(total),
+		_ => bail!(ASN1NAPIError::InvalidDataEncoding),
+	}
+}
+
+/// Write the definite length octets, using the long form when needed.
+fn write_length(length: usize, output: &mut Vec<u8>) {
+	if length < 0x80 {
+		output.push(length as u8);
+	} else {
+		let bytes = length.to_be_bytes();
+		let count = get_length_length(length) - 1;
+
+		output.push(0x80 | count as u8);
+		output.extend_from_slice(&bytes[bytes.len() - count..]);
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use num_bigint::BigInt;
+
+	use super::{encode, encode_tlv, get_element_length, get_length_length, write_length};
+	use crate::{
+		objects::{ASN1Context, ASN1Object},
+		types::ASN1Data,
+	};
+
+	fn get_nested_data(depth: usize) -> ASN1Data {
+		(0..depth).fold(ASN1Data::Integer(42), |data, level| {
+			if level % 2 == 0 {
+				ASN1Data::Array(vec![ASN1Data::Boolean(true), data])
+			} else {
+				ASN1Data::Object(ASN1Object::Context(ASN1Context::new(
+					level as u32,
+					data,
+					"explicit",
+				)))
+			}
+		})
+	}
+
+	#[test]
+	fn test_encode_matches_rasn() {
+		let data = vec![
+			get_nested_data(5),
+			ASN1Data::Set(vec![ASN1Data::Integer(1), ASN1Data::Integer(2)]),
+			ASN1Data::Set(vec![
+				ASN1Data::Array(vec![ASN1Data::Integer(3)]),
+				ASN1Data::Integer(2),
+				ASN1Data::Set(vec![ASN1Data::Boolean(true), ASN1Data::Integer(300)]),
+				ASN1Data::Integer(1),
+			]),
+			ASN1Data::Array(vec![]),
+			ASN1Data::Object(ASN1Object::Context(ASN1Context::new(
+				40,
+				ASN1Data::Null,
+				"explicit",
+			))),
+			ASN1Data::Array(vec![ASN1Data::BigInt(BigInt::from(1) << 2400)]),
+		];
+
+		for value in data {
+			assert_eq!(encode(&value).unwrap(), rasn::ber::encode(&value).unwrap());
+		}
+	}
+
+	#[test]
+	fn test_write_length() {
+		for (length, expected) in [
+			(0, vec![0x00]),
+			(127, vec![0x7f]),
+			(128, vec![0x81, 0x80]),
+			(255, vec![0x81, 0xff]),
+			(300, vec![0x82, 0x01, 0x2c]),
+			(65536, vec![0x83, 0x01, 0x00, 0x00]),
+		] {
+			let mut output = Vec::new();
+			write_length(length, &mut output);
+
+			assert_eq!(output, expected);
+		}
+	}
+
+	#[test]
+	#[cfg(target_pointer_width = "64")]
+	fn test_write_length_beyond_u32() {
+		// A 5GB content length, without allocating the contents
+		let length = 5 << 30;
+		let mut output = Vec::new();
+		write_length(length, &mut output);
+
+		assert_eq!(output, vec![0x85, 0x01, 0x40, 0x00, 0x00, 0x00]);
+		assert_eq!(get_length_length(length), 6);
+		assert_eq!(get_element_length(1, length).unwrap(), length + 7);
+	}
+
+	#[test]
+	fn test_get_element_length_overflow() {
+		assert_eq!(get_element_length(1, 300).unwrap(), 304);
+		assert!(get_element_length(1, usize::MAX).is_err());
+		assert!(get_element_length(1, isize::MAX as usize).is_err());
+	}
+
+	#[test]
+	fn test_encode_long_form_length() {
+		let data = ASN1Data::Array(vec![ASN1Data::Bytes(vec![0x41; 296])]);
+		let result = encode(&data).unwrap();
+
+		assert_eq!(
+			result[..8],
+			[0x30, 0x82, 0x01, 0x2c, 0x04, 0x82, 0x01, 0x28]
+		);
+		assert_eq!(result.len(), 304);
+		assert_eq!(result, rasn::ber::encode(&data).unwrap());
+	}
+
+	#[test]
+	fn test_encode_tlv() {
+		assert_eq!(
+			encode_tlv(0x04, &[1, 2, 3]).unwrap(),
+			vec![0x04, 0x03, 0x01, 0x02, 0x03]
+		);
+		assert_eq!(
+			encode_tlv(0x7e, &[0; 200]).unwrap()[..3],
+			[0x7e, 0x81, 0xc8]
+		);
+		assert!(encode_tlv(0x1f, &[]).is_err());
+		assert!(encode_tlv(0x100, &[]).is_err());
+	}
+
+	#[test]
+	fn test_encode_single_buffer() {
+		let data = get_nested_data(10);
+		let result = encode(&data).unwrap();
+
+		assert_eq!(result.capacity(), result.len());
+		assert_eq!(result, rasn::ber::encode(&data).unwrap());
+	}
+
+	/// Allocation benchmark against the rasn encoder, which replaces the
+	/// global allocator and so only runs with the "count-allocations"
+	/// feature, as with `make do-bench`.
+	#[cfg(feature = "count-allocations")]
+	mod allocations {
+		use std::{
+			alloc::{GlobalAlloc, Layout, System},
+			cell::Cell,
+		};
+
+		use super::{encode, get_nested_data};
+
+		/// Allocator counting the allocations made on the current thread.
+		struct CountingAllocator;
+
+		thread_local! {
+			static ALLOCATIONS: Cell<usize> = Cell::new(0);
+		}
+
+		unsafe impl GlobalAlloc for CountingAllocator {
+			unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+				let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+				System.alloc(layout)
+			}
+
+			unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+				System.dealloc(ptr, layout)
+			}
+
+			unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+				let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+				System.realloc(ptr, layout, new_size)
+			}
+		}
+
+		#[global_allocator]
+		static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+		fn count_allocations<T, F: FnOnce() -> T>(f: F) -> (T, usize) {
+			let start = ALLOCATIONS.with(Cell::get);
+			let result = f();
+
+			(result, ALLOCATIONS.with(Cell::get) - start)
+		}
+
+		#[test]
+		fn test_encode_nested_allocations() {
+			let data = get_nested_data(10);
+
+			let (expected, rasn_allocations) =
+				count_allocations(|| rasn::ber::encode(&data).unwrap());
+			let (result, allocations) = count_allocations(|| encode(&data).unwrap());
+
+			assert_eq!(result, expected);
+			assert!(
+				allocations < rasn_allocations,
+				"{} allocations is not less than {} allocations",
+				allocations,
+				rasn_allocations
+			);
+		}
+	}
+}
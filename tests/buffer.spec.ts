@@ -38,3 +38,79 @@ test('ASN1 to Js Buffer conversion round trip', (t) => {
 		t.deepEqual(lib.JStoASN1(lib.ASN1toJS(v)).toBER(), TEST_BUFFERS_ASN1[i])
 	})
 })
+
+test('Encode a TLV from a tag and contents', (t) => {
+	t.deepEqual(lib.encodeTlv(0x04, Buffer.from([1, 2, 3])), Buffer.from([0x04, 0x03, 0x01, 0x02, 0x03]))
+	t.deepEqual(lib.encodeTlv(0x61, Buffer.from([0x05, 0x00])), Buffer.from([0x61, 0x02, 0x05, 0x00]))
+	t.deepEqual(lib.encodeTlv(0x04, Buffer.alloc(130)).subarray(0, 3), Buffer.from([0x04, 0x81, 0x82]))
+	t.throws(() => lib.encodeTlv(0x1f, Buffer.from([1])))
+	t.throws(() => lib.encodeTlv(0x104, Buffer.from([1])))
+})
+
+test('Encode elements with explicit tags', (t) => {
+	const integer: lib.ASN1RawElement = { class: 'universal', tag: 2, contents: Buffer.from([0x2a]) }
+
+	t.deepEqual(
+		lib.encodeRaw({ class: 'application', tag: 5, elements: [integer] }),
+		Buffer.from([0x65, 0x03, 0x02, 0x01, 0x2a]),
+	)
+	t.deepEqual(
+		lib.encodeRaw({ class: 'context', tag: 31, elements: [integer, integer] }),
+		Buffer.from([0xbf, 0x1f, 0x06, 0x02, 0x01, 0x2a, 0x02, 0x01, 0x2a]),
+	)
+	t.throws(() => lib.encodeRaw({ class: 'other', tag: 1, contents: Buffer.from([0x00]) }))
+	t.throws(() => lib.encodeRaw({ class: 'context', tag: 1 }))
+})
+
+test('ASN1 raw tag bytes', (t) => {
+	const data = lib.encodeRaw({ class: 'application', tag: 1000, contents: Buffer.from([0x00]) })
+
+	t.deepEqual(new lib.ASN1Decoder(data).tagBytes(), Buffer.from([0x5f, 0x87, 0x68]))
+	t.deepEqual(lib.ASN1Decoder.fromHex('020101').tagBytes(), Buffer.from([0x02]))
+	t.throws(() => lib.ASN1Decoder.fromHex('7f87').tagBytes())
+})
+
+test('ASN1 to Js Buffer conversion with a maximum value length', (t) => {
+	const options: lib.ASN1DecodeOptions = { maxValueLength: 5 }
+	// A declared 1GB OCTET STRING with truncated contents
+	const truncated = new Uint8Array([0x04, 0x84, 0x40, 0x00, 0x00, 0x00, 0x01, 0x02]).buffer
+
+	t.throws(() => lib.ASN1toJS(truncated, options), { message: 'The value length exceeds the maximum value length' })
+	t.deepEqual(lib.ASN1toJS(TEST_BUFFERS_ASN1[0], options), TEST_BUFFERS[0])
+	t.throws(() => lib.ASN1toJS(TEST_BUFFERS_ASN1[0], { maxValueLength: 4 }))
+	t.throws(() => lib.ASN1toJS(lib.JStoASN1(['Keeta Test']).toBER(), options))
+})
+
+test('ASN1 with a declared length beyond 4GB', (t) => {
+	// A declared 5GB OCTET STRING with truncated contents
+	const truncated = new Uint8Array([0x04, 0x85, 0x01, 0x40, 0x00, 0x00, 0x00, 0x01, 0x02]).buffer
+
+	t.throws(() => lib.ASN1toJS(truncated))
+	t.throws(() => new lib.ASN1Decoder(truncated).intoBuffer())
+	t.deepEqual(new lib.ASN1Decoder(truncated).tagBytes(), Buffer.from([0x04]))
+})
+
+test('ASN1 elements of unknown types round trip unchanged', (t) => {
+	const data = [
+		'0a0105',
+		'300a0a010528050201010500',
+		'31060201010a0105',
+		'3009288002010100000500',
+		'1f2203010203',
+	]
+
+	data.forEach((hex) => {
+		const input = new Uint8Array(Buffer.from(hex, 'hex')).buffer
+
+		t.deepEqual(lib.JStoASN1(lib.ASN1toJS(input)).toBER(), input)
+	})
+	t.throws(() => lib.JStoASN1({ value: 1 } as any), { message: 'Unable to handle this object' })
+})
+
+test('ASN1 to Js conversion of a segmented OCTET STRING in lenient mode', (t) => {
+	const segmented = new Uint8Array(Buffer.from('2406040161040162', 'hex')).buffer
+	const mistagged = new Uint8Array(Buffer.from('24026162', 'hex')).buffer
+
+	t.deepEqual(lib.ASN1toJS(segmented, { lenient: true }), segmented)
+	t.deepEqual(lib.ASN1toJS(mistagged, { lenient: true }), Buffer.from('ab'))
+})
@@ -0,0 +1,223 @@
+import * as crypto from 'crypto'
+
+import test from 'ava'
+
+import * as lib from '..'
+
+const TEST_PUBLIC_KEY = Buffer.from(
+	lib
+		.JStoASN1([
+			[
+				{ type: 'oid', oid: 'ecdsa' },
+				{ type: 'oid', oid: 'secp256k1' },
+			],
+			{ type: 'bitstring', value: Buffer.alloc(65, 0x04) },
+		])
+		.toBER(),
+)
+
+const TEST_CERTIFICATE_OPTIONS: lib.ASN1TestCertificateOptions = {
+	subject: [
+		{ type: 'set', name: { type: 'oid', oid: '2.5.4.6' }, value: 'US' },
+		{ type: 'set', name: { type: 'oid', oid: 'commonName' }, value: 'client1.keeta.com' },
+	],
+	issuer: [{ type: 'set', name: { type: 'oid', oid: 'commonName' }, value: 'Keeta Test CA' }],
+	notBefore: new Date('2022-11-03T01:29:58Z'),
+	notAfter: new Date('2052-11-03T01:29:58Z'),
+	serial: 0xfedcba9876543210n,
+	publicKey: TEST_PUBLIC_KEY,
+	algorithm: 'sha256WithEcDSA',
+}
+
+test('Build and parse a test certificate', (t) => {
+	const certificate = lib.parseCertificate(lib.buildTestCertificate(TEST_CERTIFICATE_OPTIONS))
+
+	t.is(certificate.version, 3)
+	t.is(certificate.serial, TEST_CERTIFICATE_OPTIONS.serial)
+	t.is(certificate.algorithm, TEST_CERTIFICATE_OPTIONS.algorithm)
+	t.deepEqual(certificate.subject, TEST_CERTIFICATE_OPTIONS.subject)
+	t.deepEqual(certificate.issuer, TEST_CERTIFICATE_OPTIONS.issuer)
+	t.deepEqual(certificate.notBefore, TEST_CERTIFICATE_OPTIONS.notBefore)
+	t.deepEqual(certificate.notAfter, TEST_CERTIFICATE_OPTIONS.notAfter)
+	t.deepEqual(certificate.publicKey, TEST_PUBLIC_KEY)
+	t.deepEqual(certificate.signature, Buffer.alloc(0))
+})
+
+test('Parse an invalid certificate', (t) => {
+	t.throws(() => lib.parseCertificate(Buffer.from(lib.JStoASN1([1n, 2n]).toBER())))
+	t.throws(() => lib.buildTestCertificate({ ...TEST_CERTIFICATE_OPTIONS, publicKey: Buffer.from([0x05, 0x00]) }))
+})
+
+test('Parse a certificate with a subject unique identifier', (t) => {
+	const [tbs, algorithm] = lib.ASN1toJS(lib.buildTestCertificate(TEST_CERTIFICATE_OPTIONS)) as any[]
+	const signature = { type: 'bitstring', value: Buffer.from([0x05, 0x06]) }
+	const subjectUniqueId = Buffer.from([0x01, 0x02, 0x03, 0x04])
+	const extensions = [[{ type: 'oid', oid: '2.5.29.19' }, Buffer.from([0x30, 0x00])]]
+
+	tbs.push(
+		{ type: 'context', kind: 'implicit', value: 2, contains: Buffer.concat([Buffer.from([0x00]), subjectUniqueId]) },
+		{ type: 'context', kind: 'explicit', value: 3, contains: extensions },
+	)
+
+	const certificate = lib.parseCertificate(Buffer.from(lib.JStoASN1([tbs, algorithm, signature]).toBER()))
+
+	t.is(certificate.issuerUniqueId, undefined)
+	t.deepEqual(certificate.subjectUniqueId, subjectUniqueId)
+	t.deepEqual(certificate.subject, TEST_CERTIFICATE_OPTIONS.subject)
+	t.deepEqual(certificate.signature, signature.value)
+})
+
+test('Flatten a certificate into paths, types and values', (t) => {
+	const entries = lib.ASN1Decoder.fromBuffer(lib.buildTestCertificate(TEST_CERTIFICATE_OPTIONS)).flatten()
+	const get = (path: string) => entries.find((entry) => entry.path === path)
+
+	t.is(new Set(entries.map((entry) => entry.path)).size, entries.length)
+	t.deepEqual(get('0/0/contains'), { path: '0/0/contains', type: 'integer', value: 2n })
+	t.deepEqual(get('0/1'), { path: '0/1', type: 'integer', value: TEST_CERTIFICATE_OPTIONS.serial })
+	t.deepEqual(get('0/2/0'), { path: '0/2/0', type: 'oid', value: { type: 'oid', oid: 'sha256WithEcDSA' } })
+	t.deepEqual(get('0/5/1/0/0'), { path: '0/5/1/0/0', type: 'oid', value: { type: 'oid', oid: 'commonName' } })
+	t.deepEqual(get('0/5/1/0/1'), { path: '0/5/1/0/1', type: 'string', value: 'client1.keeta.com' })
+	t.deepEqual(get('0/4/0'), { path: '0/4/0', type: 'date', value: TEST_CERTIFICATE_OPTIONS.notBefore })
+	t.deepEqual(get('0/4/1'), { path: '0/4/1', type: 'date', value: TEST_CERTIFICATE_OPTIONS.notAfter })
+	t.is(get('0/6/1')?.type, 'bitstring')
+})
+
+test('Certificate with no well-defined expiration date', (t) => {
+	const notAfter = new Date('9999-12-31T23:59:59Z')
+	const certificate = lib.buildTestCertificate({ ...TEST_CERTIFICATE_OPTIONS, notAfter })
+	const time = Buffer.from('99991231235959Z')
+	const offset = certificate.indexOf(time) - 2
+
+	t.is(certificate[offset], 0x18)
+	t.true(lib.ASN1Decoder.fromBuffer(certificate.subarray(offset, offset + 2 + time.length)).isNoExpiry())
+	t.deepEqual(lib.parseCertificate(certificate).notAfter, notAfter)
+	t.false(new lib.ASN1Decoder(lib.JStoASN1(TEST_CERTIFICATE_OPTIONS.notAfter).toBER()).isNoExpiry())
+	t.throws(() => lib.ASN1Decoder.fromHex('020101').isNoExpiry())
+})
+
+test('Get the TBSCertificate bytes to verify a signature', (t) => {
+	const { privateKey, publicKey } = crypto.generateKeyPairSync('ec', { namedCurve: 'prime256v1' })
+	const unsigned = lib.buildTestCertificate(TEST_CERTIFICATE_OPTIONS)
+	const tbs = lib.ASN1Decoder.fromBuffer(unsigned).tbsCertificateBytes()
+
+	const header = unsigned[1] & 0x80 ? 2 + (unsigned[1] & 0x7f) : 2
+
+	t.deepEqual(tbs, unsigned.subarray(header, header + tbs.length))
+
+	const [, algorithm] = lib.ASN1toJS(unsigned) as any[]
+	const signature = { type: 'bitstring', value: crypto.sign('sha256', tbs, privateKey) }
+	const signed = Buffer.from(lib.JStoASN1([lib.ASN1toJS(tbs), algorithm, signature]).toBER())
+	const signedTbs = lib.ASN1Decoder.fromBuffer(signed).tbsCertificateBytes()
+
+	t.deepEqual(signedTbs, tbs)
+	t.true(crypto.verify('sha256', signedTbs, publicKey, lib.parseCertificate(signed).signature))
+	t.throws(() => lib.ASN1Decoder.fromBuffer(Buffer.from([0x02, 0x01, 0x01])).tbsCertificateBytes())
+})
+
+test('Parse a certificate chain', (t) => {
+	const leaf = lib.buildTestCertificate(TEST_CERTIFICATE_OPTIONS)
+	const issuer = lib.buildTestCertificate({
+		...TEST_CERTIFICATE_OPTIONS,
+		subject: TEST_CERTIFICATE_OPTIONS.issuer,
+		serial: 1n,
+	})
+
+	for (const chain of [Buffer.concat([leaf, issuer]), lib.encodeTlv(0x30, Buffer.concat([leaf, issuer]))]) {
+		const certificates = lib.ASN1Decoder.fromBuffer(chain).intoCertificateChain()
+
+		t.is(certificates.length, 2)
+		t.is(certificates[0].serial, TEST_CERTIFICATE_OPTIONS.serial)
+		t.deepEqual(certificates[0].subject, TEST_CERTIFICATE_OPTIONS.subject)
+		t.is(certificates[1].serial, 1n)
+		t.deepEqual(certificates[1].subject, TEST_CERTIFICATE_OPTIONS.issuer)
+	}
+
+	t.is(lib.ASN1Decoder.fromBuffer(leaf).intoCertificateChain().length, 1)
+	t.throws(() => lib.ASN1Decoder.fromBuffer(Buffer.concat([leaf, Buffer.from([0x02, 0x01, 0x01])])).intoCertificateChain())
+})
+
+test('Parse certificates asynchronously', async (t) => {
+	const certificates = Array.from({ length: 16 }, (_, index) =>
+		lib.buildTestCertificate({ ...TEST_CERTIFICATE_OPTIONS, serial: BigInt(index + 1) }),
+	)
+
+	t.deepEqual(
+		await lib.parseCertificatesAsync(certificates),
+		certificates.map((certificate) => lib.parseCertificate(certificate)),
+	)
+	t.deepEqual(await lib.parseCertificatesAsync([]), [])
+	await t.throwsAsync(lib.parseCertificatesAsync([certificates[0], Buffer.from(lib.JStoASN1([1n, 2n]).toBER())]))
+})
+
+test('Match a certificate against a certificate shaped schema', (t) => {
+	const certificate = lib.ASN1Decoder.fromBuffer(lib.buildTestCertificate(TEST_CERTIFICATE_OPTIONS))
+	const name = { seq: '*' }
+	const algorithm = { seq: [{ oid: true }] }
+	const schema = {
+		seq: [
+			{
+				seq: [
+					'context',
+					'integer',
+					algorithm,
+					name,
+					{ seq: ['date', 'date'] },
+					name,
+					{ seq: [{ seq: '*' }, 'bitstring'] },
+				],
+			},
+			algorithm,
+			'bitstring',
+		],
+	}
+
+	t.true(certificate.matchesSchema(schema))
+	t.true(certificate.matchesSchema({ seq: [{ seq: '*' }, { seq: '*' }, { bitstring: true }] }))
+	t.false(certificate.matchesSchema({ seq: ['any', 'any'] }))
+	t.false(lib.ASN1Decoder.fromHex('3006020101020102').matchesSchema(schema))
+	t.false(lib.ASN1Decoder.fromHex('020101').matchesSchema({ seq: '*' }))
+	t.throws(() => certificate.matchesSchema({ sequence: '*', set: '*' }))
+	t.throws(() => certificate.matchesSchema({ integer: '*' }))
+	t.throws(() => certificate.matchesSchema('unknown'))
+})
+
+test('Encode a TBSCertificate with its SHA-256 digest', (t) => {
+	const certificate = lib.ASN1Decoder.fromBuffer(lib.buildTestCertificate(TEST_CERTIFICATE_OPTIONS))
+	const encoder = new lib.ASN1Encoder(certificate.at('0'))
+	const { der, digest } = encoder.encodeWithDigest('sha256')
+
+	t.deepEqual(der, Buffer.from(encoder.toBER()))
+	t.deepEqual(digest, crypto.createHash('sha256').update(der).digest())
+	t.deepEqual(encoder.encodeWithDigest('sha384').digest, crypto.createHash('sha384').update(der).digest())
+	t.deepEqual(encoder.encodeWithDigest('sha512').digest, crypto.createHash('sha512').update(der).digest())
+	t.throws(() => encoder.encodeWithDigest('sha3-256' as any))
+})
+
+test('Convert a certificate between DER and PEM', (t) => {
+	const der = lib.buildTestCertificate(TEST_CERTIFICATE_OPTIONS)
+	const pem = lib.derToPem(der, 'CERTIFICATE')
+	const lines = pem.trimEnd().split('\n')
+
+	t.is(lines[0], '-----BEGIN CERTIFICATE-----')
+	t.is(lines[lines.length - 1], '-----END CERTIFICATE-----')
+	t.true(lines.slice(1, -1).every((line) => line.length <= 64))
+	t.deepEqual(lib.pemToDer(pem), der)
+	t.deepEqual(lib.pemToDer(pem + lib.derToPem(Buffer.from([0x30, 0x00]), 'CERTIFICATE')), der)
+	t.deepEqual(lib.pemToDer(pem + '-----BEGIN CERTIFICATE-----\nM!A=\n'), der)
+	t.throws(() => lib.pemToDer(der.toString('base64')))
+	t.throws(() => lib.derToPem(der, 'X509-CERTIFICATE'))
+})
+
+test('Decode every certificate of a PEM bundle', (t) => {
+	const first = lib.buildTestCertificate(TEST_CERTIFICATE_OPTIONS)
+	const second = lib.buildTestCertificate({ ...TEST_CERTIFICATE_OPTIONS, serial: 2n })
+	const bundle = `# First\n${lib.derToPem(first, 'CERTIFICATE')}\n# Second\n${lib.derToPem(second, 'CERTIFICATE')}`
+	const certificates = lib.pemToDerAll(bundle)
+
+	t.deepEqual(certificates, [first, second])
+	t.deepEqual(
+		certificates.map((certificate) => lib.parseCertificate(certificate).serial),
+		[TEST_CERTIFICATE_OPTIONS.serial, 2n],
+	)
+	t.throws(() => lib.pemToDerAll(''))
+})
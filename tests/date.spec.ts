@@ -48,3 +48,96 @@ test('ASN1 to Js Date conversion round trip', (t) => {
 		t.deepEqual(lib.JStoASN1(lib.ASN1toJS(v)).toBER(), TEST_DATES_ASN1[i])
 	})
 })
+
+test('ASN1 to Js Date conversion with date formats', (t) => {
+	const input = TEST_DATES_ASN1[3]
+
+	t.deepEqual(lib.ASN1toJS(input), new Date('2052-09-26T10:10:32.420+00:00'))
+	t.deepEqual(lib.ASN1toJS(input, { dateFormat: 'date' }), new Date('2052-09-26T10:10:32.420+00:00'))
+	t.is(lib.ASN1toJS(input, { dateFormat: 'iso' }), '2052-09-26T10:10:32.420Z')
+	t.is(lib.ASN1toJS(input, { dateFormat: 'asn1' }), '20520926101032.420Z')
+
+	const sequence = lib.JStoASN1([new Date('2052-09-26T10:10:32.420+00:00')]).toBER()
+	const decoder = new lib.ASN1Decoder(sequence, { dateFormat: 'iso' })
+
+	t.deepEqual(decoder.intoArray(), ['2052-09-26T10:10:32.420Z'])
+})
+
+test('ASN1 to Js Date conversion without seconds', (t) => {
+	const utc = Buffer.from('\x17\x0b2206221818Z', 'latin1')
+	const general = Buffer.from('\x18\x0d202206221818Z', 'latin1')
+	const date = new Date('2022-06-22T18:18:00+00:00')
+
+	t.deepEqual(new lib.ASN1Decoder(utc).intoDate(), date)
+	t.deepEqual(new lib.ASN1Decoder(general).intoDate(), date)
+	t.deepEqual(lib.ASN1toJS(new Uint8Array(utc).buffer), date)
+})
+
+test('ASN1 UTCTime and GeneralizedTime keep their kind in a round trip', (t) => {
+	const times = [
+		// UTCTime 220926100000Z
+		'170d3232303932363130303030305a',
+		// GeneralizedTime 20220926100000Z
+		'180f32303232303932363130303030305a',
+		// GeneralizedTime 20520926100000Z
+		'180f32303532303932363130303030305a',
+	]
+
+	times.forEach((hex) => {
+		const input = new Uint8Array(Buffer.from(hex, 'hex')).buffer
+
+		t.deepEqual(lib.JStoASN1(lib.ASN1toJS(input)).toBER(), input)
+		t.deepEqual(lib.JStoASN1([lib.ASN1toJS(input)]).toBER().slice(2), input)
+	})
+})
+
+test('JS Date of the no-expiry year 9999 to ASN1 GeneralizedTime', (t) => {
+	const date = new Date('9999-12-31T23:59:59Z')
+	const der = Buffer.from(lib.JStoASN1(date).toBER())
+
+	t.deepEqual(der, Buffer.from('\x18\x0f99991231235959Z', 'latin1'))
+	t.deepEqual(lib.ASN1toJS(der), date)
+	t.true(lib.ASN1Decoder.fromBuffer(der).isNoExpiry())
+	t.throws(() => lib.JStoASN1(new Date('+010000-01-01T00:00:00Z')).toBER())
+	t.throws(() => lib.JStoASN1({ type: 'date', kind: 'utc', date }).toBER())
+})
+
+test('JS Date to ASN1 with a date encoding policy', (t) => {
+	const date = new Date('2020-01-01T00:00:00.250Z')
+	const encode = (data: any, policy: lib.ASN1DateEncodingPolicy) =>
+		Buffer.from(new lib.ASN1Encoder(data, policy).toBER()).toString('latin1')
+
+	t.is(encode(date, { alwaysGeneral: true, fractionalDigits: 0 }), '\x18\x0f20200101000000Z')
+	t.is(encode(date, { alwaysGeneral: true, fractionalDigits: 4 }), '\x18\x1220200101000000.25Z')
+	t.is(encode(new Date('2020-01-01T00:00:00Z'), { alwaysGeneral: true, fractionalDigits: 3 }), '\x18\x0f20200101000000Z')
+	t.is(encode(date, { cutoffYear: 2000 }), '\x18\x1320200101000000.250Z')
+	t.is(encode(date, {}), '\x17\x0d200101000000Z')
+	t.is(encode([{ type: 'date', kind: 'utc', date }], { alwaysGeneral: true }), '\x30\x0f\x17\x0d200101000000Z')
+	t.is(
+		encode({ type: 'date', kind: 'general', date, offset: 60 }, { preserveOffset: false, fractionalDigits: 0 }),
+		'\x18\x0f20200101000000Z',
+	)
+	t.throws(() => new lib.ASN1Encoder(date, { cutoffYear: 2051 }))
+	t.throws(() => new lib.ASN1Encoder(date, { fractionalDigits: 10 }))
+})
+
+test('ASN1 GeneralizedTime to nanoseconds keeping the sub-millisecond precision', (t) => {
+	const general = Buffer.from('\x18\x1620220926100000.123456Z', 'latin1')
+	const utc = Buffer.from('\x17\x0d220926100000Z', 'latin1')
+
+	t.is(new lib.ASN1Decoder(general).intoTimeNanos(), 1664186400123456000n)
+	t.deepEqual(new lib.ASN1Decoder(general).intoDate(), new Date('2022-09-26T10:00:00.123Z'))
+	t.is(new lib.ASN1Decoder(utc).intoTimeNanos(), 1664186400000000000n)
+	t.throws(() => new lib.ASN1Decoder(Buffer.from([0x02, 0x01, 0x01])).intoTimeNanos())
+})
+
+test('ASN1 CMS signed attributes to SigningTime', (t) => {
+	const contentType = '301806092a864886f70d010903310b06092a864886f70d010701'
+	const signingTime = '301c06092a864886f70d010905310f170d3232303932363130303030305a'
+	const date = new Date('2022-09-26T10:00:00Z')
+
+	t.deepEqual(lib.ASN1Decoder.fromHex(`3138${contentType}${signingTime}`).intoSigningTime(), date)
+	t.deepEqual(lib.ASN1Decoder.fromHex(`a038${contentType}${signingTime}`).intoSigningTime(), date)
+	t.is(lib.ASN1Decoder.fromHex(`311a${contentType}`).intoSigningTime(), null)
+	t.throws(() => lib.ASN1Decoder.fromHex('3000').intoSigningTime())
+})
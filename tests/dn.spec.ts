@@ -0,0 +1,66 @@
+import test from 'ava'
+
+import * as lib from '..'
+
+function getName(organization: lib.ASN1String | string, commonName: lib.ASN1String | string): Buffer {
+	return Buffer.from(
+		lib
+			.JStoASN1([
+				{ type: 'set', name: { type: 'oid', oid: '2.5.4.6' }, value: 'US' },
+				{ type: 'set', name: { type: 'oid', oid: '2.5.4.10' }, value: organization },
+				{ type: 'set', name: { type: 'oid', oid: 'commonName' }, value: commonName },
+			])
+			.toBER(),
+	)
+}
+
+test('Canonicalize DN', (t) => {
+	const name = getName('Keeta', 'node1.keeta.com')
+
+	t.is(lib.canonicalizeDn(name), 'CN=node1.keeta.com,O=keeta,C=us')
+	t.throws(() => lib.canonicalizeDn(Buffer.from([0x02, 0x01, 0x01])))
+})
+
+test('Canonicalize DNs differing in case and spacing', (t) => {
+	const name = getName('Keeta Network', 'node1.keeta.com')
+	const other = getName({ type: 'string', kind: 'utf8', value: ' KEETA  network' }, 'Node1.Keeta.com')
+
+	t.notDeepEqual(name, other)
+	t.is(lib.canonicalizeDn(name), lib.canonicalizeDn(other))
+})
+
+test('Canonicalize DN with a multi-valued RDN', (t) => {
+	const commonName = [{ type: 'oid', oid: 'commonName' }, 'foo']
+	const unit = [{ type: 'oid', oid: '2.5.4.11' }, 'bar']
+	const name = Buffer.from(lib.JStoASN1([{ type: 'set', value: [commonName, unit] }]).toBER())
+	const other = Buffer.from(lib.JStoASN1([{ type: 'set', value: [unit, commonName] }]).toBER())
+
+	t.is(lib.canonicalizeDn(name), 'CN=foo+OU=bar')
+	t.is(lib.canonicalizeDn(other), 'CN=foo+OU=bar')
+})
+
+test('JS multi-valued RDN to ASN1 conversion in DER order', (t) => {
+	const commonName = [{ type: 'oid', oid: 'commonName' }, 'foo']
+	const unit = [{ type: 'oid', oid: '2.5.4.11' }, 'bar']
+	const expected = new Uint8Array([
+		0x31, 0x18, 0x30, 0x0a, 0x06, 0x03, 0x55, 0x04, 0x03, 0x13, 0x03, 0x66, 0x6f, 0x6f, 0x30, 0x0a,
+		0x06, 0x03, 0x55, 0x04, 0x0b, 0x13, 0x03, 0x62, 0x61, 0x72,
+	]).buffer
+
+	t.deepEqual(lib.JStoASN1({ type: 'set', value: [commonName, unit] }).toBER(), expected)
+	t.deepEqual(lib.JStoASN1({ type: 'set', value: [unit, commonName] }).toBER(), expected)
+	t.deepEqual(lib.JStoASN1([{ type: 'set', value: [unit, commonName] }]).toBER().slice(2), expected)
+})
+
+test('Match DNs differing in string type', (t) => {
+	const name = getName('Keeta', 'node1.keeta.com')
+	const other = getName(
+		{ type: 'string', kind: 'utf8', value: 'KEETA' },
+		{ type: 'string', kind: 'utf8', value: 'node1.keeta.com' },
+	)
+
+	t.notDeepEqual(name, other)
+	t.true(lib.dnMatches(name, other))
+	t.false(lib.dnMatches(name, getName('Keeta', 'node2.keeta.com')))
+	t.throws(() => lib.dnMatches(name, Buffer.from([0x02, 0x01, 0x01])))
+})
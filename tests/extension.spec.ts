@@ -0,0 +1,232 @@
+import test from 'ava'
+
+import * as lib from '..'
+
+// GeneralNames with a dNSName of "example.com" and a UPN otherName of
+// "user@example.com"
+const TEST_SAN_UPN =
+	'302f820b6578616d706c652e636f6da020060a2b060104018237140203a0120c1075736572406578616d706c652e636f6d'
+
+// GeneralNames with a UPN otherName of "user" as a BMPString
+const TEST_SAN_UPN_BMP = '301aa018060a2b060104018237140203a00a1e080075007300650072'
+
+test('ASN1 SAN to Js User Principal Name conversion', (t) => {
+	t.is(lib.ASN1Decoder.fromHex(TEST_SAN_UPN).intoUserPrincipalName(), 'user@example.com')
+	t.is(lib.ASN1Decoder.fromHex(TEST_SAN_UPN_BMP).intoUserPrincipalName(), 'user')
+	t.is(lib.ASN1Decoder.fromHex('300d820b6578616d706c652e636f6d').intoUserPrincipalName(), null)
+})
+
+test('ASN1 certificate to Js User Principal Name conversion', (t) => {
+	const certificate = lib.JStoASN1([
+		[
+			{ type: 'context', kind: 'explicit', value: 0, contains: 2n },
+			1n,
+			{
+				type: 'context',
+				kind: 'explicit',
+				value: 3,
+				contains: [
+					[{ type: 'oid', oid: 'subjectAltName' }, Buffer.from(TEST_SAN_UPN, 'hex')],
+				],
+			},
+		],
+	]).toBER()
+
+	t.is(new lib.ASN1Decoder(certificate).intoUserPrincipalName(), 'user@example.com')
+})
+
+test('JS extension with a raw value to ASN1 conversion', (t) => {
+	// BasicConstraints with cA set
+	const value = Buffer.from([0x30, 0x03, 0x01, 0x01, 0xff])
+	const critical = lib.encodeExtension('2.5.29.19', true, value)
+	const nonCritical = lib.encodeExtension('subjectAltName', false, Buffer.from(TEST_SAN_UPN, 'hex'))
+
+	t.deepEqual(
+		critical,
+		Buffer.from([
+			0x30, 0x0f, 0x06, 0x03, 0x55, 0x1d, 0x13, 0x01, 0x01, 0xff, 0x04, 0x05, 0x30, 0x03, 0x01, 0x01, 0xff,
+		]),
+	)
+	t.deepEqual(new lib.ASN1Decoder(critical).intoExtensions(), [{ oid: '2.5.29.19', critical: true, value }])
+	t.deepEqual(new lib.ASN1Decoder(nonCritical).intoExtensions(), [
+		{ oid: 'subjectAltName', critical: false, value: Buffer.from(TEST_SAN_UPN, 'hex') },
+	])
+
+	const extensions = Buffer.concat([Buffer.from([0x30, critical.length + nonCritical.length]), critical, nonCritical])
+	t.is(new lib.ASN1Decoder(extensions).intoExtensions().length, 2)
+	t.is(new lib.ASN1Decoder(extensions).intoUserPrincipalName(), 'user@example.com')
+})
+
+test('ASN1 extensionRequest attribute to Js conversion', (t) => {
+	const extension = [{ type: 'oid', oid: 'subjectAltName' }, Buffer.from(TEST_SAN_UPN, 'hex')]
+	const attribute = lib.JStoASN1([
+		{ type: 'oid', oid: 'extensionRequest' },
+		{ type: 'set', value: [[extension]] },
+	]).toBER()
+
+	t.deepEqual(new lib.ASN1Decoder(attribute).intoAttribute(), {
+		type: { type: 'oid', oid: 'extensionRequest' },
+		values: [[extension]],
+	})
+	t.throws(() => new lib.ASN1Decoder(lib.JStoASN1([{ type: 'oid', oid: 'commonName' }, 'test']).toBER()).intoAttribute())
+})
+
+test('ASN1 certificate policies to Js policies conversion', (t) => {
+	// CertificatePolicies with the anyPolicy OID and no qualifiers
+	t.deepEqual(lib.ASN1Decoder.fromHex('300830060604551d2000').intoCertificatePolicies(), [
+		{ policy: { type: 'oid', oid: 'anyPolicy' }, qualifiers: [] },
+	])
+
+	const qualifier = [{ type: 'oid', oid: '1.3.6.1.5.5.7.2.1' }, { type: 'string', kind: 'ia5', value: 'https://keeta.com/cps' }]
+	const policies = lib.JStoASN1([[{ type: 'oid', oid: '1.2.3.4' }, [qualifier]]]).toBER()
+
+	t.deepEqual(lib.ASN1Decoder.fromBuffer(Buffer.from(policies)).intoCertificatePolicies(), [
+		{ policy: { type: 'oid', oid: '1.2.3.4' }, qualifiers: [qualifier] },
+	])
+	t.throws(() => lib.ASN1Decoder.fromHex('3003020101').intoCertificatePolicies())
+})
+
+test('ASN1 CRL distribution points to Js distribution points conversion', (t) => {
+	const url = 'http://crl.keeta.com/ca.crl'
+	// DistributionPoint { distributionPoint [0] { fullName [0] { uniformResourceIdentifier [6] } } }
+	const points = lib.encodeRaw({
+		class: 'universal',
+		tag: 16,
+		elements: [
+			{
+				class: 'universal',
+				tag: 16,
+				elements: [
+					{
+						class: 'context',
+						tag: 0,
+						elements: [{ class: 'context', tag: 0, elements: [{ class: 'context', tag: 6, contents: Buffer.from(url) }] }],
+					},
+				],
+			},
+		],
+	})
+	const extension = lib.encodeExtension('cRLDistributionPoints', false, points)
+
+	t.deepEqual(lib.ASN1Decoder.fromBuffer(points).intoCrlDistributionPoints(), [{ urls: [url] }])
+	t.deepEqual(lib.ASN1Decoder.fromBuffer(extension).intoExtensions()[0].oid, 'cRLDistributionPoints')
+	t.deepEqual(lib.ASN1Decoder.fromHex('30023000').intoCrlDistributionPoints(), [{ urls: [] }])
+	t.throws(() => lib.ASN1Decoder.fromHex('3003020101').intoCrlDistributionPoints())
+})
+
+test('ASN1 authority information access to Js access descriptions conversion', (t) => {
+	const url = 'http://ocsp.keeta.com'
+	const descriptions = lib.encodeRaw({
+		class: 'universal',
+		tag: 16,
+		elements: [
+			{
+				class: 'universal',
+				tag: 16,
+				elements: [
+					{ class: 'universal', tag: 6, contents: Buffer.from('2b06010505073001', 'hex') },
+					{ class: 'context', tag: 6, contents: Buffer.from(url) },
+				],
+			},
+		],
+	})
+
+	t.deepEqual(lib.ASN1Decoder.fromBuffer(descriptions).intoAuthorityInfoAccess(), [
+		{ method: { type: 'oid', oid: 'ocsp' }, location: url },
+	])
+	t.deepEqual(lib.ASN1Decoder.fromBuffer(lib.encodeExtension('authorityInfoAccess', false, descriptions)).intoExtensions()[0].oid, 'authorityInfoAccess')
+	t.throws(() => lib.ASN1Decoder.fromHex('3003020101').intoAuthorityInfoAccess())
+})
+
+test('Find the value of an extension by its OID', (t) => {
+	const extensions = lib.JStoASN1([
+		[{ type: 'oid', oid: 'subjectAltName' }, Buffer.from('3000', 'hex')],
+		[{ type: 'oid', oid: '2.5.29.19' }, true, Buffer.from('3000', 'hex')],
+	]).toBER()
+	const decoder = lib.ASN1Decoder.fromBuffer(Buffer.from(extensions))
+
+	t.deepEqual(decoder.findByOid('2.5.29.19'), Buffer.from('04023000', 'hex'))
+	t.deepEqual(decoder.findByOid('subjectAltName'), Buffer.from('04023000', 'hex'))
+	t.is(decoder.findByOid('2.5.29.31'), null)
+	t.throws(() => lib.ASN1Decoder.fromHex('020101').findByOid('2.5.29.19'))
+})
+
+test('Match the authority key identifier against an issuer subject key identifier', (t) => {
+	const keyId = Buffer.from('7aa996960f667509d70ed16257cb97822082f32b', 'hex')
+	const aki = lib.encodeExtension('2.5.29.35', false, Buffer.concat([Buffer.from('30168014', 'hex'), keyId]))
+	const extensions = Buffer.concat([Buffer.from([0x30, aki.length]), aki])
+
+	t.true(lib.ASN1Decoder.fromBuffer(aki).authorityKeyIdMatches(keyId))
+	t.true(lib.ASN1Decoder.fromBuffer(extensions).authorityKeyIdMatches(keyId))
+	t.false(lib.ASN1Decoder.fromBuffer(extensions).authorityKeyIdMatches(keyId.subarray(1)))
+	t.false(lib.ASN1Decoder.fromBuffer(lib.encodeExtension('2.5.29.19', false, Buffer.from('3000', 'hex'))).authorityKeyIdMatches(keyId))
+})
+
+test('Encode GeneralNames', (t) => {
+	const name = Buffer.from(lib.JStoASN1([{ type: 'set', name: { type: 'oid', oid: 'commonName' }, value: 'Test' }]).toBER())
+
+	t.deepEqual(
+		lib.encodeGeneralName({ type: 'dNSName', value: 'example.com' }),
+		Buffer.from('820b6578616d706c652e636f6d', 'hex'),
+	)
+	t.deepEqual(lib.encodeGeneralName({ type: 'rfc822Name', value: 'a@b.c' }), Buffer.from('81056140622e63', 'hex'))
+	t.deepEqual(
+		lib.encodeGeneralName({ type: 'uniformResourceIdentifier', value: 'http://a' }),
+		Buffer.from('8608687474703a2f2f61', 'hex'),
+	)
+	t.deepEqual(lib.encodeGeneralName({ type: 'iPAddress', value: '192.0.2.1' }), Buffer.from('8704c0000201', 'hex'))
+	t.deepEqual(
+		lib.encodeGeneralName({ type: 'iPAddress', value: '2001:db8::1' }),
+		Buffer.from('871020010db8000000000000000000000001', 'hex'),
+	)
+	t.deepEqual(
+		lib.encodeGeneralName({ type: 'iPAddress', value: Buffer.from([10, 0, 0, 0, 255, 0, 0, 0]) }),
+		Buffer.from('87080a000000ff000000', 'hex'),
+	)
+	t.deepEqual(lib.encodeGeneralName({ type: 'registeredID', value: '1.2.3.4' }), Buffer.from('88032a0304', 'hex'))
+	t.deepEqual(
+		lib.encodeGeneralName({ type: 'directoryName', value: name }),
+		Buffer.concat([Buffer.from([0xa4, name.length]), name]),
+	)
+
+	t.throws(() => lib.encodeGeneralName({ type: 'dNSName', value: 'exämple.com' }))
+	t.throws(() => lib.encodeGeneralName({ type: 'iPAddress', value: '192.0.2' }))
+	t.throws(() => lib.encodeGeneralName({ type: 'directoryName', value: 'CN=Test' }))
+	t.throws(() => lib.encodeGeneralName({ type: 'x400Address', value: 'test' } as any))
+})
+
+test('Decode GeneralNames with Punycode domains', (t) => {
+	const inputs: lib.ASN1GeneralName[] = [
+		{ type: 'rfc822Name', value: 'user@xn--bcher-kva.example' },
+		{ type: 'dNSName', value: 'www.xn--mnchen-3ya.de' },
+		{ type: 'uniformResourceIdentifier', value: 'http://xn--bcher-kva.example' },
+		{ type: 'iPAddress', value: Buffer.from([192, 0, 2, 1]) },
+		{ type: 'registeredID', value: '1.2.3.4' },
+	]
+	const names = inputs.map((input) => lib.encodeGeneralName(input))
+	const decoder = lib.ASN1Decoder.fromBuffer(lib.encodeTlv(0x30, Buffer.concat(names)))
+
+	t.deepEqual(decoder.intoGeneralNames(), inputs)
+	t.deepEqual(
+		decoder.intoGeneralNames({ decodePunycode: true }).map((name) => name.unicode),
+		['user@bücher.example', 'www.münchen.de', undefined, undefined, undefined],
+	)
+	t.deepEqual(
+		decoder.intoGeneralNames({ decodePunycode: true }).map((name) => name.value),
+		inputs.map((input) => input.value),
+	)
+	t.deepEqual(
+		lib.ASN1Decoder.fromBuffer(
+			lib.encodeTlv(
+				0x30,
+				Buffer.concat([
+					lib.encodeGeneralName({ type: 'dNSName', value: 'xn--bcher-kva_.example' }),
+					lib.encodeGeneralName({ type: 'dNSName', value: 'xn--bcher-kva.example' }),
+				]),
+			),
+		)
+			.intoGeneralNames({ decodePunycode: true })
+			.map((name) => name.unicode),
+		[undefined, 'bücher.example'],
+	)
+})
@@ -121,3 +121,47 @@ test('JS BigInt to Buffer conversion helper', (t) => {
 		t.deepEqual(lib.BufferToBigInt(buffer), v)
 	})
 })
+
+test('ASN1 serial number with a leading zero round trip', (t) => {
+	const serials = [
+		new Uint8Array([0x02, 0x03, 0x00, 0xff, 0xff]).buffer,
+		new Uint8Array([0x02, 0x09, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]).buffer,
+	]
+
+	serials.forEach((v) => {
+		t.deepEqual(lib.JStoASN1(lib.ASN1toJS(v)).toBER(), v)
+		t.deepEqual(lib.JStoASN1([lib.ASN1toJS(v)]).toBER().slice(2), v)
+	})
+	t.is(lib.ASN1toJS(serials[0]), 0xffffn)
+})
+
+test('JS positive BigInt with the top bit set to ASN1 conversion', (t) => {
+	t.deepEqual(lib.JStoASN1(0xffffn).toBER(), new Uint8Array([0x02, 0x03, 0x00, 0xff, 0xff]).buffer)
+	t.deepEqual(lib.JStoASN1(0x80n).toBER(), new Uint8Array([0x02, 0x02, 0x00, 0x80]).buffer)
+	t.is(lib.ASN1toJS(lib.JStoASN1(0xffffn).toBER()), 0xffffn)
+})
+
+test('JS integer from exact bytes to ASN1 conversion', (t) => {
+	t.deepEqual(lib.JStoASN1({ type: 'integer', bytes: [0x00, 0xff] }).toBER(), new Uint8Array([0x02, 0x02, 0x00, 0xff]).buffer)
+	t.deepEqual(
+		lib.JStoASN1([{ type: 'integer', bytes: Buffer.from([0x00, 0x00, 0x01]) }]).toBER(),
+		new Uint8Array([0x30, 0x05, 0x02, 0x03, 0x00, 0x00, 0x01]).buffer,
+	)
+	t.throws(() => lib.JStoASN1({ type: 'integer', bytes: [] }))
+})
+
+test('ASN1 integer to little-endian bytes conversion', (t) => {
+	t.deepEqual(lib.ASN1Decoder.fromHex('02020100').intoIntegerBytesLE(), Buffer.from([0x00, 0x01]))
+	t.deepEqual(lib.ASN1Decoder.fromHex('0203008001').intoIntegerBytesLE(), Buffer.from([0x01, 0x80]))
+	t.throws(() => lib.ASN1Decoder.fromHex('0201ff').intoIntegerBytesLE(), { message: 'The provided integer is negative' })
+})
+
+test('ASN1 sequence of integers to a BigInt array conversion', (t) => {
+	const values = [2n ** 64n, -(2n ** 200n), 12345678901234567890123n]
+	const decoder = new lib.ASN1Decoder(lib.JStoASN1(values).toBER())
+
+	t.deepEqual(decoder.intoBigIntArray(), values)
+	t.deepEqual(lib.ASN1Decoder.fromHex('3000').intoBigIntArray(), [])
+	t.throws(() => lib.ASN1Decoder.fromHex('3003010101').intoBigIntArray())
+	t.throws(() => lib.ASN1Decoder.fromHex('020101').intoBigIntArray(), { message: 'The provided ASN1 data is malformed and cannot be decoded' })
+})
@@ -0,0 +1,33 @@
+import test from 'ava'
+
+import * as lib from '..'
+
+const TEST_NULL_ASN1 = new Uint8Array([0x05, 0x00]).buffer
+
+test('JS null to ASN1 conversion', (t) => {
+	t.deepEqual(lib.JStoASN1(null).toBER(), TEST_NULL_ASN1)
+})
+
+test('ASN1 to JS null conversion', (t) => {
+	t.is(lib.ASN1toJS(TEST_NULL_ASN1), null)
+	t.is(lib.ASN1Decoder.fromHex('0500').intoNull(), null)
+	t.throws(() => lib.ASN1Decoder.fromHex('020100').intoNull())
+})
+
+test('ASN1 to JS null conversion within constructed data', (t) => {
+	// AlgorithmIdentifier with NULL parameters
+	const algorithm = new Uint8Array([
+		0x30, 0x0d, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b, 0x05, 0x00,
+	]).buffer
+
+	t.deepEqual(lib.ASN1toJS(algorithm), [{ type: 'oid', oid: '1.2.840.113549.1.1.11' }, null])
+	t.is(lib.ASN1Decoder.fromHex('300d06092a864886f70d01010b0500').at('1'), null)
+	t.deepEqual(lib.ASN1toJS(new Uint8Array([0x30, 0x05, 0x05, 0x00, 0x02, 0x01, 0x01]).buffer), [null, 1n])
+	t.deepEqual(lib.ASN1toJS(new Uint8Array([0xa0, 0x02, 0x05, 0x00]).buffer), {
+		type: 'context',
+		kind: 'explicit',
+		value: 0,
+		contains: null,
+	})
+	t.deepEqual(lib.JStoASN1(lib.ASN1toJS(algorithm)).toBER(), algorithm)
+})
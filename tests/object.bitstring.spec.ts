@@ -121,3 +121,21 @@ test('ASN1 to Js ASN1BitString conversion round trip', (t) => {
 		t.deepEqual(lib.JStoASN1(lib.ASN1toJS(v)).toBER(), TEST_BITSTRINGS_ASN1[i])
 	})
 })
+
+test('ASN1 BitString unused bits without decoding the content', (t) => {
+	t.is(lib.ASN1Decoder.fromHex('030205a0').bitStringUnusedBits(), 5)
+	TEST_BITSTRINGS_ASN1.forEach((v, i) => {
+		t.is(new lib.ASN1Decoder(v).bitStringUnusedBits(), TEST_BITSTRINGS[i].unusedBits ?? 0)
+	})
+	t.throws(() => lib.ASN1Decoder.fromHex('040205a0').bitStringUnusedBits())
+})
+
+test('ASN1 BitString encapsulating a DER structure', (t) => {
+	const signature = Buffer.from(lib.JStoASN1([1n, 2n]).toBER())
+	const bitstring = lib.JStoASN1({ type: 'bitstring', value: signature }).toBER()
+	const decoder = new lib.ASN1Decoder(bitstring).intoBitStringEncapsulated()
+
+	t.deepEqual(decoder.intoArray(), [1n, 2n])
+	t.throws(() => lib.ASN1Decoder.fromHex('0303010500').intoBitStringEncapsulated())
+	t.throws(() => lib.ASN1Decoder.fromHex('030400050000').intoBitStringEncapsulated())
+})
@@ -98,3 +98,23 @@ test('ASN1 to Js Context Tag conversion round trip', (t) => {
 		)
 	})
 })
+
+test('ASN1 Context Tag constructed bit', (t) => {
+	const constructed = new lib.ASN1Decoder(new Uint8Array([0xa0, 0x03, 0x02, 0x01, 0x2a]).buffer)
+	const primitive = new lib.ASN1Decoder(new Uint8Array([0x80, 0x01, 0x2a]).buffer)
+
+	t.true(constructed.contextTagIsConstructed())
+	t.false(primitive.contextTagIsConstructed())
+	t.throws(() => new lib.ASN1Decoder(new Uint8Array([0x02, 0x01, 0x2a]).buffer).contextTagIsConstructed())
+})
+
+test('ASN1 empty explicit Context Tag round trip', (t) => {
+	const empty = new Uint8Array([0xa0, 0x00]).buffer
+	const sequence = new Uint8Array([0x30, 0x05, 0xa1, 0x00, 0x02, 0x01, 0x01]).buffer
+
+	t.deepEqual(lib.ASN1toJS(empty), { type: 'context', kind: 'explicit', value: 0 })
+	t.deepEqual(new lib.ASN1Decoder(empty).intoContextTag(), { type: 'context', kind: 'explicit', value: 0 })
+	t.deepEqual(lib.JStoASN1({ type: 'context', kind: 'explicit', value: 0 }).toBER(), empty)
+	t.deepEqual(lib.JStoASN1(lib.ASN1toJS(empty)).toBER(), empty)
+	t.deepEqual(lib.JStoASN1(lib.ASN1toJS(sequence)).toBER(), sequence)
+})
@@ -74,4 +74,37 @@ test('ASN1 to Js ANS1Date conversion round trip', (t) => {
     TEST_DATES_ASN1.forEach((v, i) => {
         t.deepEqual(lib.JStoASN1(lib.ASN1toJS(v)).toBER(), TEST_DATES_ASN1[i])
 	})
-})
\ No newline at end of file
+})
+
+test('JS ASN1Date outside of the UTCTime range to ASN1 conversion', (t) => {
+	const date = new Date(Date.UTC(1940, 0, 1))
+
+	t.throws(() => lib.JStoASN1({ type: 'date', kind: 'utc', date }))
+	t.throws(() => lib.JStoASN1({ type: 'date', kind: 'utc', date: new Date(2524694400001) }))
+
+	// 19400101000000Z
+	const general = lib.JStoASN1({ type: 'date', date }).toBER()
+	t.is(new Uint8Array(general)[0], 0x18)
+	t.deepEqual(lib.JStoASN1(date).toBER(), general)
+	t.deepEqual(lib.ASN1toJS(general), date)
+})
+
+test('ASN1 GeneralizedTime with a time zone offset round trip', (t) => {
+	// 20220926100000+0800
+	const input = Buffer.from('181332303232303932363130303030302b30383030', 'hex')
+	const date = new Date(Date.UTC(2022, 8, 26, 2))
+	const arrayBuffer = input.buffer.slice(input.byteOffset, input.byteOffset + input.length)
+
+	t.deepEqual(lib.ASN1toJS(arrayBuffer), { type: 'date', kind: 'general', date })
+	t.deepEqual(lib.ASN1toJS(arrayBuffer, { preserveTimeOffset: true }), {
+		type: 'date',
+		kind: 'general',
+		date,
+		offset: 480,
+	})
+	t.deepEqual(lib.JStoASN1(lib.ASN1toJS(arrayBuffer, { preserveTimeOffset: true })).toBER(), arrayBuffer)
+	t.deepEqual(
+		Buffer.from(lib.JStoASN1(lib.ASN1toJS(arrayBuffer)).toBER()),
+		Buffer.from('180f32303232303932363032303030305a', 'hex'),
+	)
+})
@@ -47,3 +47,55 @@ test('ASN1 to Js ASN1OID conversion round trip', (t) => {
 		t.deepEqual(lib.JStoASN1(lib.ASN1toJS(v)).toBER(), TEST_OIDS_ASN1[i])
 	})
 })
+
+test('ASN1 to Js ASN1OID conversion with constructed bit set in lenient mode', (t) => {
+	const input = new Uint8Array([0x26, 0x03, 0x55, 0x04, 0x03]).buffer
+	const sequence = new Uint8Array([0x30, 0x05, 0x26, 0x03, 0x55, 0x04, 0x03]).buffer
+
+	t.deepEqual(lib.ASN1toJS(input), input)
+	t.deepEqual(lib.ASN1toJS(input, { lenient: true }), TEST_OIDS[1])
+	t.deepEqual(lib.ASN1toJS(sequence, { lenient: true }), [TEST_OIDS[1]])
+})
+
+test('JS OID prefix comparison', (t) => {
+	t.true(lib.oidStartsWith('1.3.6.1.4.1.311.20', '1.3.6.1.4.1'))
+	t.true(lib.oidStartsWith('1.3.6.1.4.1', '1.3.6.1.4.1'))
+	t.true(lib.oidStartsWith('commonName', '2.5.4'))
+	t.false(lib.oidStartsWith('1.3.10', '1.3.1'))
+	t.false(lib.oidStartsWith('1.3.6', '1.3.6.1'))
+	t.throws(() => lib.oidStartsWith('notAnOid', '1.3'))
+})
+
+test('JS signature algorithm family from OID', (t) => {
+	t.is(lib.signatureAlgorithmFamily('sha256WithEcDSA'), 'ecdsa')
+	t.is(lib.signatureAlgorithmFamily('sha3-256WithEcDSA'), 'ecdsa')
+	t.is(lib.signatureAlgorithmFamily('ed25519'), 'eddsa')
+	t.is(lib.signatureAlgorithmFamily('1.2.840.113549.1.1.11'), 'rsa')
+	t.is(lib.signatureAlgorithmFamily('commonName'), 'unknown')
+	t.throws(() => lib.signatureAlgorithmFamily('notAnOid'))
+})
+
+test('JS OID object and string conversion', (t) => {
+	const oid: lib.ASN1OID = { type: 'oid', oid: 'commonName' }
+
+	t.is(lib.oidToString(oid), 'commonName')
+	t.deepEqual(lib.stringToOid('commonName'), oid)
+	t.deepEqual(lib.stringToOid('2.5.4.3'), { type: 'oid', oid: '2.5.4.3' })
+	t.deepEqual(lib.stringToOid(lib.oidToString(oid)), oid)
+	t.throws(() => lib.stringToOid('notAnOid'))
+	t.throws(() => lib.oidToString({ type: 'oid', oid: 'notAnOid' }))
+})
+
+test('ASN1 OID to JS with an OID mode', (t) => {
+	const known = lib.JStoASN1({ type: 'oid', oid: 'commonName' }).toBER()
+	const unknown = lib.JStoASN1({ type: 'oid', oid: '1.2.3.4' }).toBER()
+
+	t.deepEqual(lib.ASN1toJS(known, { oidMode: 'name' }), { type: 'oid', oid: 'commonName' })
+	t.deepEqual(lib.ASN1toJS(known, { oidMode: 'dotted' }), { type: 'oid', oid: '2.5.4.3' })
+	t.deepEqual(lib.ASN1toJS(known, { oidMode: 'both' }), { type: 'oid', oid: '2.5.4.3', name: 'commonName' })
+	t.deepEqual(lib.ASN1toJS(unknown, { oidMode: 'dotted' }), { type: 'oid', oid: '1.2.3.4' })
+	t.deepEqual(lib.ASN1toJS(unknown, { oidMode: 'both' }), { type: 'oid', oid: '1.2.3.4' })
+	t.throws(() => lib.ASN1toJS(unknown, { oidMode: 'name' }))
+	t.deepEqual(new lib.ASN1Decoder(unknown, { oidMode: 'dotted' }).intoOid(), { type: 'oid', oid: '1.2.3.4' })
+	t.throws(() => new lib.ASN1Decoder(unknown, { oidMode: 'name' }).intoOid())
+})
@@ -41,3 +41,12 @@ test('ASN1 to Js ASN1Set conversion round trip', (t) => {
 		t.deepEqual(lib.JStoASN1(lib.ASN1toJS(v)).toBER(), TEST_SETS_ASN1[i])
 	})
 })
+
+test('ASN1Set with an empty string value round trip', (t) => {
+	const set: lib.ASN1Set = { type: 'set', name: { type: 'oid', oid: 'commonName' }, value: '' }
+	const input = new Uint8Array([0x31, 0x09, 0x30, 0x07, 0x06, 0x03, 0x55, 0x04, 0x03, 0x13, 0x00]).buffer
+
+	t.deepEqual(lib.JStoASN1(set).toBER(), input)
+	t.deepEqual(lib.ASN1toJS(input), set)
+	t.deepEqual(lib.ASN1toJS(lib.JStoASN1([set, '']).toBER()), [set, ''])
+})
@@ -46,3 +46,48 @@ test('ASN1 to Js ANS1String conversion round trip', (t) => {
         t.deepEqual(lib.JStoASN1(lib.ASN1toJS(v)).toBER(), TEST_STRINGS_ASN1[i])
 	})
 })
+
+test('ASN1 to Js NUL padded ASN1String conversion', (t) => {
+	const padded = new Uint8Array([0x13, 0x6, 0x54, 0x65, 0x73, 0x74, 0x00, 0x00]).buffer
+	const options: lib.ASN1DecodeOptions = { trimNul: true }
+
+	t.deepEqual(new lib.ASN1Decoder(padded, options).intoString(), 'Test')
+	t.deepEqual(lib.ASN1toJS(padded, options), 'Test')
+	t.deepEqual(lib.JStoASN1(lib.ASN1toJS(padded, options)).toBER(), TEST_STRINGS_ASN1[0])
+})
+
+test('ASN1 TeletexString to Js conversion and transcoding', (t) => {
+	const teletex = new Uint8Array([0x14, 0x4, 0x63, 0x61, 0x66, 0xe9]).buffer
+	const utf8 = new Uint8Array([0xc, 0x5, 0x63, 0x61, 0x66, 0xc3, 0xa9]).buffer
+	const value: lib.ASN1String = { type: 'string', kind: 'teletex', value: 'café' }
+
+	t.is(new lib.ASN1Decoder(teletex).intoString(), 'café')
+	t.deepEqual(lib.ASN1toJS(teletex), value)
+	t.deepEqual(lib.JStoASN1(lib.ASN1toJS(teletex)).toBER(), teletex)
+	t.is(lib.ASN1toJS(teletex, { teletexAsUtf8: true }), 'café')
+	t.deepEqual(lib.JStoASN1(lib.ASN1toJS(teletex, { teletexAsUtf8: true })).toBER(), utf8)
+	t.throws(() => lib.JStoASN1({ type: 'string', kind: 'teletex', value: 'Tesᄳ' }))
+
+	const set: lib.ASN1Set = { type: 'set', name: { type: 'oid', oid: 'commonName' }, value }
+	t.deepEqual(lib.ASN1toJS(lib.JStoASN1(set).toBER()), set)
+})
+
+test('ASN1 UTF8String of printable characters keeps its kind in a round trip', (t) => {
+	const utf8 = '0c025553'
+	const values = [
+		utf8,
+		`3004${utf8}`,
+		// countryName attribute of a DN
+		`310b30090603550406${utf8}`,
+		`300d310b30090603550406${utf8}`,
+	]
+
+	values.forEach((hex) => {
+		const input = new Uint8Array(Buffer.from(hex, 'hex')).buffer
+
+		t.deepEqual(lib.JStoASN1(lib.ASN1toJS(input)).toBER(), input)
+	})
+
+	t.deepEqual(lib.ASN1toJS(Buffer.from(utf8, 'hex')), { type: 'string', kind: 'utf8', value: 'US' })
+	t.deepEqual(lib.JStoASN1('US').toBER(), new Uint8Array([0x13, 0x02, 0x55, 0x53]).buffer)
+})
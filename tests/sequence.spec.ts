@@ -67,3 +67,128 @@ test('ASN1 to Js mixed array conversion round trip', (t) => {
 		t.deepEqual(lib.JStoASN1(lib.ASN1toJS(v)).toBER(), TEST_SEQUENCES_ASN1[i])
 	})
 })
+
+test('JS explicit sequence and set wrappers to ASN1 conversion', (t) => {
+	const values = [1n, 2n, 3n]
+	const sequence: lib.ASN1Sequence = { type: 'sequence', value: values }
+	const set: lib.ASN1SetOf = { type: 'set', value: values }
+
+	t.deepEqual(lib.JStoASN1(sequence).toBER(), lib.JStoASN1(values).toBER())
+	t.deepEqual(
+		lib.JStoASN1(set).toBER(),
+		new Uint8Array([0x31, 0x09, 0x02, 0x01, 0x01, 0x02, 0x01, 0x02, 0x02, 0x01, 0x03]).buffer,
+	)
+})
+
+test('JS empty sequence and set to ASN1 conversion round trip', (t) => {
+	const sequence = new Uint8Array([0x30, 0x00]).buffer
+	const set = new Uint8Array([0x31, 0x00]).buffer
+	const emptySet: lib.ASN1SetOf = { type: 'set', value: [] }
+
+	t.deepEqual(lib.JStoASN1([]).toBER(), sequence)
+	t.deepEqual(lib.JStoASN1({ type: 'sequence', value: [] }).toBER(), sequence)
+	t.deepEqual(lib.JStoASN1(emptySet).toBER(), set)
+	t.deepEqual(lib.ASN1toJS(sequence), [])
+	t.deepEqual(lib.ASN1toJS(set), emptySet)
+})
+
+test('ASN1 generic set to Js conversion', (t) => {
+	const input = new Uint8Array([0x31, 0x09, 0x02, 0x01, 0x01, 0x02, 0x01, 0x02, 0x02, 0x01, 0x03]).buffer
+
+	t.deepEqual(lib.ASN1toJS(input), { type: 'set', value: [1n, 2n, 3n] })
+})
+
+test('JS sequence with long form length to ASN1 conversion', (t) => {
+	const content = Buffer.alloc(296, 0x41)
+	const sequence = new Uint8Array(lib.JStoASN1([content]).toBER())
+	const bitString = new Uint8Array(
+		lib.JStoASN1({ type: 'bitstring', value: Buffer.alloc(299, 0x41) }).toBER(),
+	)
+	const implicit = new Uint8Array(
+		lib.JStoASN1({ type: 'context', kind: 'implicit', value: 3, contains: Buffer.alloc(300, 0x41) }).toBER(),
+	)
+
+	t.deepEqual(Array.from(sequence.subarray(0, 8)), [0x30, 0x82, 0x01, 0x2c, 0x04, 0x82, 0x01, 0x28])
+	t.is(sequence.length, 304)
+	t.deepEqual(lib.ASN1toJS(sequence.buffer), [content])
+	t.deepEqual(Array.from(bitString.subarray(0, 5)), [0x03, 0x82, 0x01, 0x2c, 0x00])
+	t.is(bitString.length, 304)
+	t.deepEqual(Array.from(implicit.subarray(0, 4)), [0x83, 0x82, 0x01, 0x2c])
+	t.is(implicit.length, 304)
+})
+
+test('ASN1 to Js conversion of the element at a path', (t) => {
+	const obj = new lib.ASN1Decoder(
+		lib.JStoASN1([1n, [2n, { type: 'context', kind: 'explicit', value: 0, contains: [3n, 'test'] }]]).toBER(),
+	)
+
+	t.is(obj.at('0'), 1n)
+	t.deepEqual(obj.at('1/1/contains'), [3n, 'test'])
+	t.is(obj.at('1/1/contains/1'), 'test')
+	t.throws(() => obj.at('2'))
+	t.throws(() => obj.at('0/contains'))
+})
+
+test('ASN1 Sequence to Js array conversion with a schema', (t) => {
+	const data = lib.JStoASN1([42n, { type: 'oid', oid: 'commonName' }, ['Test', true]]).toBER()
+	const sequence = lib.ASN1Decoder.fromBuffer(Buffer.from(data))
+
+	t.deepEqual(sequence.intoSchema(['integer', 'oid', 'sequence']), [
+		42n,
+		{ type: 'oid', oid: 'commonName' },
+		['Test', true],
+	])
+	t.deepEqual(sequence.intoSchema(['any', 'any', 'any']), lib.ASN1toJS(data))
+	t.deepEqual(lib.ASN1Decoder.fromHex('3006020101020102').intoSchema(['integer', 'integer']), [1n, 2n])
+	t.throws(() => sequence.intoSchema(['oid', 'integer', 'sequence']), { message: 'The provided data does not match the schema' })
+	t.throws(() => sequence.intoSchema(['integer', 'oid']))
+})
+
+test('ASN1 sequence to head and tail', (t) => {
+	const decoder = lib.ASN1Decoder.fromHex('3009020101020102040103')
+	const { head, tail } = decoder.intoHeadAndTail(2)
+
+	t.deepEqual(head, [1n, 2n])
+	t.deepEqual(tail?.intoArray(), [Buffer.from([0x03])])
+	t.deepEqual(decoder.intoHeadAndTail(1).tail?.intoArray(), [2n, Buffer.from([0x03])])
+	t.deepEqual(decoder.intoHeadAndTail(3), { head: [1n, 2n, Buffer.from([0x03])], tail: null })
+	t.throws(() => decoder.intoHeadAndTail(4), { message: 'The provided head count exceeds the number of elements' })
+	t.throws(() => lib.ASN1Decoder.fromHex('020101').intoHeadAndTail(0), { message: 'The provided ASN1 data is malformed and cannot be decoded' })
+
+	// A single trailing Sequence is wrapped like several elements
+	const nested = lib.ASN1Decoder.fromHex('300702010130020500').intoHeadAndTail(1)
+
+	t.deepEqual(nested.tail?.intoArray(), [[null]])
+})
+
+test('ASN1 to a tree of raw elements', (t) => {
+	const vote = Buffer.from(
+		'MFEGCWCGSAFlAwQCCDBEBCCb0PJlcOIUeBZH8vNeObY9pgxw+6PUh6ku6n9k9VVYDgQge0hOYtjbsjyJqqx5m7D8iP+i6dLBTcFsl/kwxUkaO1k=',
+		'base64',
+	)
+	const tree = lib.ASN1Decoder.fromBuffer(vote).intoRawTree() as any[]
+	const rebuild = (node: Buffer | any[]): Buffer =>
+		Array.isArray(node) ? lib.encodeTlv(0x30, Buffer.concat(node.map(rebuild))) : node
+
+	t.is(tree.length, 2)
+	t.is(tree[0][0], 0x06)
+	t.true(tree[1].every((leaf: Buffer) => Buffer.isBuffer(leaf) && leaf[0] === 0x04))
+	t.deepEqual(rebuild(tree), vote)
+	t.deepEqual(lib.ASN1Decoder.fromHex('020101').intoRawTree(), Buffer.from([0x02, 0x01, 0x01]))
+	t.deepEqual(lib.ASN1Decoder.fromHex('a1053003020101').intoRawTree(), [[Buffer.from([0x02, 0x01, 0x01])]])
+})
+
+test('ASN1 shape fingerprint', (t) => {
+	const data = lib
+		.JStoASN1([
+			1n,
+			{ type: 'oid', oid: 'commonName' },
+			[true],
+			{ type: 'set', name: { type: 'oid', oid: 'commonName' }, value: 'test' },
+		])
+		.toBER()
+
+	t.is(new lib.ASN1Decoder(data).shapeFingerprint(), 'seq(int,oid,seq,set)')
+	t.is(lib.ASN1Decoder.fromHex('020101').shapeFingerprint(), 'int')
+	t.is(lib.ASN1Decoder.fromHex('a0030101ff').shapeFingerprint(), 'ctx0(bool)')
+})